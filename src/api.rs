@@ -1,10 +1,35 @@
-use std::{sync::LazyLock, time::Duration};
+use std::{
+	collections::HashSet,
+	path::PathBuf,
+	sync::{
+		LazyLock,
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+	},
+	time::{Duration, Instant},
+};
 
-use reqwest::{Client, Response, Result, header};
+use reqwest::{Client, Method, RequestBuilder, Response, Result, StatusCode, header};
 use semver::Version;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-#[derive(Debug, Clone, Deserialize)]
+mod local;
+
+use crate::auth::{AuthKind, Credentials};
+use crate::network::{self, NetworkSettings};
+use crate::signing::{self, ManifestError, SignaturePolicy};
+use crate::tr;
+
+/// Hoster API version this client speaks
+pub const API_VERSION: u32 = 1;
+/// Oldest hoster API version this client still works with
+const MIN_SERVER_API_VERSION: u32 = 1;
+
+// INFO: unknown fields are ignored and missing ones defaulted where possible,
+// so small additions on the hoster's side don't break older clients
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct ZipFile {
 	pub size: u64,
 	pub is_present: bool,
@@ -14,9 +39,54 @@ pub struct ZipFile {
 #[derive(Debug, Clone, Deserialize)]
 pub struct Mod {
 	pub name: String,
+	#[serde(default)]
 	pub mod_date: f64,
 	pub size: u64,
+	#[serde(default)]
 	pub is_optional: bool,
+	/// Hex encoded sha256 of the file, older servers don't send it
+	#[serde(default)]
+	pub hash: Option<String>,
+}
+
+/// How the optional mods of a group can be picked
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupKind {
+	/// Any of them, like ungrouped optional mods
+	#[default]
+	Any,
+	/// At most one of them, e.g. shaders
+	OneOf,
+	/// Every one of them or none, e.g. a performance pack
+	AllOrNothing,
+}
+
+/// Optional mods shown and picked together
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModGroup {
+	pub name: String,
+	#[serde(default)]
+	pub kind: GroupKind,
+	/// File names of the optional mods in it
+	pub mods: Vec<String>,
+}
+
+/// Named selection of optional mods, e.g. "Low end PC"
+#[derive(Debug, Clone, Deserialize)]
+pub struct Preset {
+	pub name: String,
+	/// File names of the optional mods it selects, every other optional is unselected
+	pub mods: Vec<String>,
+}
+
+impl Preset {
+	/// Case insensitive lookup by name
+	pub fn find<'a>(presets: &'a [Preset], name: &str) -> Option<&'a Preset> {
+		presets
+			.iter()
+			.find(|v| v.name.to_lowercase() == name.trim().to_lowercase())
+	}
 }
 
 pub type BranchNames = Vec<String>;
@@ -25,18 +95,296 @@ pub type Mods = Vec<Mod>;
 #[derive(Debug, Clone, Deserialize)]
 pub struct BranchInfo {
 	pub mods: Mods,
+	#[serde(default)]
 	pub zip: ZipFile,
+	/// Older hosters don't send groups, every optional mod stands on its own then
+	#[serde(default)]
+	pub groups: Vec<ModGroup>,
+	#[serde(default)]
+	pub presets: Vec<Preset>,
+}
+
+/// What the hoster says about itself on {api}/version
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ServerInfo {
+	pub api_version: u32,
+	/// Oldest client API version the hoster still serves
+	pub min_client_version: u32,
+	pub capabilities: Vec<String>,
+}
+
+impl Default for ServerInfo {
+	/// Hosters from before versioning
+	fn default() -> Self {
+		Self {
+			api_version: 1,
+			min_client_version: 1,
+			capabilities: Vec::new(),
+		}
+	}
+}
+
+impl ServerInfo {
+	/// Err if client can't work with the hoster, Ok(Some) with a message if it may only partly work
+	pub fn check_compatibility(&self) -> std::result::Result<Option<String>, String> {
+		if self.min_client_version > API_VERSION {
+			return Err(tr!(
+				"compat.client_too_old",
+				required = self.min_client_version,
+				version = API_VERSION
+			));
+		}
+
+		if self.api_version < MIN_SERVER_API_VERSION {
+			return Ok(Some(tr!(
+				"compat.server_too_old",
+				version = self.api_version,
+				required = MIN_SERVER_API_VERSION
+			)));
+		}
+
+		// INFO: still serves this client, but new features of the hoster won't show up
+		if self.api_version > API_VERSION {
+			return Ok(Some(tr!(
+				"compat.server_newer",
+				version = self.api_version,
+				client = API_VERSION
+			)));
+		}
+
+		Ok(None)
+	}
+}
+
+/// HTTP validators of a response, sent back so the hoster can answer "not modified"
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Validators {
+	pub etag: Option<String>,
+	pub last_modified: Option<String>,
+}
+
+impl Validators {
+	fn from_response(res: &Response) -> Self {
+		let get = |name| {
+			res.headers()
+				.get(name)
+				.and_then(|v| v.to_str().ok())
+				.map(String::from)
+		};
+
+		Self {
+			etag: get(header::ETAG),
+			last_modified: get(header::LAST_MODIFIED),
+		}
+	}
+
+	fn apply(&self, mut builder: RequestBuilder) -> RequestBuilder {
+		if let Some(etag) = self.etag.as_ref() {
+			builder = builder.header(header::IF_NONE_MATCH, etag);
+		}
+		if let Some(last_modified) = self.last_modified.as_ref() {
+			builder = builder.header(header::IF_MODIFIED_SINCE, last_modified);
+		}
+
+		builder
+	}
+}
+
+/// Result of a conditional request
+#[derive(Debug)]
+pub enum Fetched<T> {
+	Changed(T, Validators),
+	NotModified,
+}
+
+static CLIENT: LazyLock<std::sync::RwLock<Client>> = LazyLock::new(|| {
+	std::sync::RwLock::new(network::build_client(&NetworkSettings::default(), None).unwrap())
+});
+/// Settings CLIENT was built with, it's rebuilt with them when the primary server changes
+static NETWORK_SETTINGS: LazyLock<std::sync::RwLock<NetworkSettings>> =
+	LazyLock::new(|| std::sync::RwLock::new(NetworkSettings::default()));
+
+/// Client with the user's network settings, for requests outside the hoster too
+pub fn get_client() -> Client {
+	CLIENT.read().unwrap().clone()
+}
+
+/// Use network settings (proxy, certificates, timeouts) for every following request to the hoster
+pub fn set_network_settings(settings: &NetworkSettings) -> std::result::Result<(), String> {
+	let client = network::build_client(settings, primary_host().as_deref())?;
+	*CLIENT.write().unwrap() = client;
+	*NETWORK_SETTINGS.write().unwrap() = settings.clone();
+
+	Ok(())
+}
+
+static CREDENTIALS: LazyLock<std::sync::RwLock<Option<Credentials>>> =
+	LazyLock::new(|| std::sync::RwLock::new(None));
+
+/// Set credentials sent with every request to the hoster, None for anonymous requests
+pub fn set_credentials(credentials: Option<Credentials>) {
+	*CREDENTIALS.write().unwrap() = credentials;
+}
+
+/// Host and port of url, None if it isn't a valid url
+fn host_of(url: &str) -> Option<(String, Option<u16>)> {
+	let url = reqwest::Url::parse(url).ok()?;
+	Some((url.host_str()?.to_string(), url.port_or_known_default()))
+}
+
+/// Host of the primary server, None before one is set
+fn primary_host() -> Option<String> {
+	ADDRESSES
+		.read()
+		.unwrap()
+		.first()
+		.and_then(|v| host_of(v))
+		.map(|v| v.0)
+}
+
+/// Returns wether path goes to the primary server's host, not to a mirror or anywhere else
+fn is_on_primary(path: &str) -> bool {
+	let addresses = ADDRESSES.read().unwrap();
+	// INFO: nothing is the primary before a server is set, unknown hosts never get credentials
+	let primary = match addresses.first().and_then(|v| host_of(v)) {
+		Some(primary) => primary,
+		None => return false,
+	};
+
+	host_of(path).is_some_and(|v| v == primary)
+}
+
+/// Build a request to the hoster with the current credentials
+/// Credentials were entered for the primary server, mirrors (third parties) never get them
+fn request(method: Method, path: String) -> RequestBuilder {
+	let is_on_primary = is_on_primary(&path);
+	let builder = get_client().request(method, path);
+
+	if !is_on_primary {
+		return builder;
+	}
+
+	match CREDENTIALS.read().unwrap().as_ref() {
+		None => builder,
+		Some(credentials) => match &credentials.kind {
+			AuthKind::Basic { username } => builder.basic_auth(username, Some(&credentials.secret)),
+			AuthKind::Bearer => builder.bearer_auth(&credentials.secret),
+			AuthKind::Header { name } => builder.header(name.as_str(), &credentials.secret),
+		},
+	}
+}
+
+/// Primary main address followed by its mirrors, in the order they should be tried
+static ADDRESSES: LazyLock<std::sync::RwLock<Vec<String>>> =
+	LazyLock::new(|| std::sync::RwLock::new(Vec::new()));
+/// Index of the address which answered last, tried first next time
+static ACTIVE_ADDRESS: AtomicUsize = AtomicUsize::new(0);
+
+/// Set mirrors of the primary main address, requests fail over to them in order
+pub fn set_mirrors(primary: &str, mirrors: &[String]) {
+	let mut addresses = vec![primary.to_string()];
+	addresses.extend(
+		mirrors
+			.iter()
+			.map(|v| v.trim().trim_end_matches('/').to_string())
+			.filter(|v| !v.is_empty() && v != primary),
+	);
+
+	*ADDRESSES.write().unwrap() = addresses;
+	ACTIVE_ADDRESS.store(0, Ordering::Relaxed);
+
+	// INFO: the pinned certificate belongs to the primary's host
+	let settings = NETWORK_SETTINGS.read().unwrap().clone();
+	match network::build_client(&settings, primary_host().as_deref()) {
+		Ok(client) => *CLIENT.write().unwrap() = client,
+		Err(err) => println!("couldn't rebuild client for {}: {}", primary, err),
+	}
+}
+
+/// Main address of the primary server or mirror which answered last
+pub fn active_address() -> Option<String> {
+	let addresses = ADDRESSES.read().unwrap();
+	addresses
+		.get(ACTIVE_ADDRESS.load(Ordering::Relaxed))
+		.or(addresses.first())
+		.cloned()
+}
+
+/// Returns wether the last answer came from a mirror instead of the primary server
+pub fn is_using_mirror() -> bool {
+	ACTIVE_ADDRESS.load(Ordering::Relaxed) != 0
+}
+
+/// Path rewritten to every address (last working first), path itself if it isn't on the primary's
+fn failover_paths(path: &str) -> Vec<(usize, String)> {
+	let addresses = ADDRESSES.read().unwrap();
+	let rest = match addresses
+		.first()
+		.and_then(|v| path.strip_prefix(v.as_str()))
+	{
+		Some(rest) => rest,
+		None => return vec![(0, path.to_string())],
+	};
+
+	let active = ACTIVE_ADDRESS.load(Ordering::Relaxed);
+	let mut order: Vec<usize> = (0..addresses.len()).collect();
+	if active < order.len() {
+		order.remove(active);
+		order.insert(0, active);
+	}
+
+	order
+		.into_iter()
+		.map(|i| (i, format!("{}{}", addresses[i], rest)))
+		.collect()
 }
 
-fn get_client() -> &'static Client {
-	static CLIENT: LazyLock<Client> = LazyLock::new(|| {
-		Client::builder()
-			.connect_timeout(Duration::from_secs(2))
-			.build()
-			.unwrap()
-	});
+/// Send request to the primary server, failing over to mirrors if it's unreachable or broken (5xx)
+async fn send(path: String, build: impl Fn(String) -> RequestBuilder) -> Result<Response> {
+	let paths = failover_paths(&path);
+	let last = paths.len() - 1;
 
-	&CLIENT
+	for (n, (i, path)) in paths.into_iter().enumerate() {
+		match build(path.clone()).send().await {
+			Ok(res) if res.status().is_server_error() && n != last => {
+				println!("{} answered {}, trying next mirror", path, res.status());
+			}
+			Err(err) if (err.is_connect() || err.is_timeout()) && n != last => {
+				println!("{} unreachable, trying next mirror: {}", path, err);
+			}
+			res => {
+				if res.is_ok() {
+					ACTIVE_ADDRESS.store(i, Ordering::Relaxed);
+				}
+				return res;
+			}
+		}
+	}
+
+	unreachable!("there is always at least one path")
+}
+
+static SIGNATURE_POLICY: LazyLock<std::sync::RwLock<SignaturePolicy>> =
+	LazyLock::new(|| std::sync::RwLock::new(SignaturePolicy::default()));
+
+/// Set how branch manifests' signatures are checked
+pub fn set_signature_policy(policy: SignaturePolicy) {
+	*SIGNATURE_POLICY.write().unwrap() = policy;
+}
+
+/// Keys the user didn't trust this session
+static DECLINED_KEYS: LazyLock<std::sync::RwLock<HashSet<String>>> =
+	LazyLock::new(|| std::sync::RwLock::new(HashSet::new()));
+
+/// Don't ask to trust key again this session, only matters while signatures aren't required
+pub fn decline_key(key: &str) {
+	DECLINED_KEYS.write().unwrap().insert(key.to_string());
+}
+
+/// Returns wether the hoster refused the request because of missing or wrong credentials
+pub fn is_unauthorized(err: &reqwest::Error) -> bool {
+	err.status() == Some(StatusCode::UNAUTHORIZED)
 }
 
 /// Get this project's latest released version
@@ -65,34 +413,292 @@ pub async fn get_repo_version() -> std::result::Result<Version, Box<dyn std::err
 }
 
 pub async fn website_exists(api_address: &str) -> Result<bool> {
+	if local::root(api_address).is_some() {
+		return Ok(true);
+	}
+
 	let path = format!("{}/mods", api_address);
-	let res = get_client().head(path).send().await?;
+	let res = send(path, |p| request(Method::HEAD, p)).await?;
 
 	Ok(res.status().is_success())
 }
 
-pub async fn get_branch_names(api_address: &str) -> Result<BranchNames> {
+/// Ask the hoster which API version it speaks
+/// Hosters without the version endpoint are treated as version 1
+pub async fn get_server_info(api_address: &str) -> Result<ServerInfo> {
+	if local::root(api_address).is_some() {
+		return Ok(ServerInfo::default());
+	}
+
+	let path = format!("{}/version", api_address);
+	let res = send(path, |p| request(Method::GET, p)).await?;
+
+	if res.status() == StatusCode::NOT_FOUND {
+		return Ok(ServerInfo::default());
+	}
+
+	// INFO: garbage from a very old or misconfigured hoster is treated as version 1 too
+	Ok(res
+		.error_for_status()?
+		.json::<ServerInfo>()
+		.await
+		.unwrap_or_default())
+}
+
+/// Measure the round trip time of a small request to the hoster
+pub async fn measure_latency(api_address: &str) -> Result<Duration> {
+	if local::root(api_address).is_some() {
+		return Ok(Duration::ZERO);
+	}
+
 	let path = format!("{}/mods", api_address);
-	let res = get_client()
-		.get(path)
-		.send()
+	let start = Instant::now();
+	send(path, |p| request(Method::HEAD, p)).await?;
+
+	Ok(start.elapsed())
+}
+
+/// Raw response of a listing, kept on disk so it can be reused when unchanged or offline
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct CachedResponse {
+	validators: Validators,
+	body: String,
+	/// Signature header, so cached manifests can be verified again
+	signature: Option<String>,
+}
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Returns wether the last listing came from the on-disk cache because the hoster was unreachable
+pub fn is_offline() -> bool {
+	OFFLINE.load(Ordering::Relaxed)
+}
+
+fn response_cache_path(path: &str) -> PathBuf {
+	dirs::config_dir()
+		.expect("Couldnt access OS's default config dir")
+		.join("minecraft-mod-syncer")
+		.join("http_cache")
+		.join(format!("{}.json", hex::encode(Sha256::digest(path))))
+}
+
+async fn load_cached_response(path: &str) -> Option<CachedResponse> {
+	let json = tokio::fs::read_to_string(response_cache_path(path))
+		.await
+		.ok()?;
+	serde_json::from_str(&json).ok()
+}
+
+async fn save_cached_response(path: &str, cached: &CachedResponse) {
+	let cache_path = response_cache_path(path);
+	let json = serde_json::to_string(cached).expect("Failed to convert response to json");
+
+	if let Some(dir) = cache_path.parent() {
+		let _ = tokio::fs::create_dir_all(dir).await;
+	}
+	if let Err(err) = tokio::fs::write(cache_path, json).await {
+		println!("Failed to cache response: {}", err);
+	}
+}
+
+/// GET path only if it changed since validators were received
+async fn get_if_changed(path: String, validators: &Validators) -> Result<Fetched<CachedResponse>> {
+	let res = send(path, |p| validators.apply(request(Method::GET, p)))
 		.await?
-		.json::<BranchNames>()
-		.await?;
+		.error_for_status()?;
 
-	Ok(res)
+	if res.status() == StatusCode::NOT_MODIFIED {
+		return Ok(Fetched::NotModified);
+	}
+
+	let validators = Validators::from_response(&res);
+	let signature = res
+		.headers()
+		.get(signing::SIGNATURE_HEADER)
+		.and_then(|v| v.to_str().ok())
+		.map(String::from);
+	let body = res.text().await?;
+
+	Ok(Fetched::Changed(
+		CachedResponse {
+			validators: validators.clone(),
+			body,
+			signature,
+		},
+		validators,
+	))
 }
 
-pub async fn get_mods_in_branch(api_address: &str, branch_name: &str) -> Result<BranchInfo> {
-	let path = format!("{}/mods/{}", api_address, branch_name);
-	let res = get_client()
-		.get(path)
+/// GET path, revalidating the on-disk copy with the hoster
+/// Falls back to the on-disk copy if the hoster is unreachable
+async fn get_cached(path: String) -> Result<CachedResponse> {
+	let cached = load_cached_response(&path).await;
+	let validators = cached
+		.as_ref()
+		.map(|v| v.validators.clone())
+		.unwrap_or_default();
+
+	match get_if_changed(path.clone(), &validators).await {
+		Ok(Fetched::Changed(res, _)) => {
+			OFFLINE.store(false, Ordering::Relaxed);
+			save_cached_response(&path, &res).await;
+			Ok(res)
+		}
+		Ok(Fetched::NotModified) => {
+			OFFLINE.store(false, Ordering::Relaxed);
+			// INFO: validators were only sent if there was a cached response
+			Ok(cached.unwrap())
+		}
+		Err(err) if err.is_connect() || err.is_timeout() => match cached {
+			Some(cached) => {
+				println!("Hoster unreachable, using cached {}: {}", path, err);
+				OFFLINE.store(true, Ordering::Relaxed);
+				Ok(cached)
+			}
+			None => Err(err),
+		},
+		Err(err) => Err(err),
+	}
+}
+
+/// Check manifest body's signature according to the signature policy
+/// Signature is checked whenever a key is trusted, but only required if the policy says so
+async fn verify_manifest(
+	api_address: &str,
+	body: &[u8],
+	signature: Option<&str>,
+) -> std::result::Result<(), ManifestError> {
+	let policy = SIGNATURE_POLICY.read().unwrap().clone();
+	let key = policy.trusted_key.as_deref().and_then(signing::parse_key);
+
+	match (signature, key) {
+		(Some(signature), Some(key)) => signing::verify(&key, body, signature),
+		(None, _) if policy.required => Err(ManifestError::Unsigned),
+		// INFO: trust on first use, asked even if signatures aren't required so the key gets pinned
+		(Some(_), None) => {
+			let server_key = get_signing_key(api_address).await?;
+
+			if policy.required || !DECLINED_KEYS.read().unwrap().contains(&server_key) {
+				return Err(ManifestError::UntrustedKey(server_key));
+			}
+
+			Ok(())
+		}
+		_ => Ok(()),
+	}
+}
+
+/// Local sources can't be signed
+fn check_local_policy() -> std::result::Result<(), ManifestError> {
+	if SIGNATURE_POLICY.read().unwrap().required {
+		return Err(ManifestError::Unsigned);
+	}
+
+	Ok(())
+}
+
+/// Get branch names, from the on-disk cache if they didn't change or the hoster is unreachable
+pub async fn get_branch_names(
+	api_address: &str,
+) -> std::result::Result<BranchNames, ManifestError> {
+	if let Some(root) = local::root(api_address) {
+		return Ok(local::get_branch_names(&root)?);
+	}
+
+	let path = format!("{}/mods", api_address);
+	let res = get_cached(path).await?;
+
+	Ok(serde_json::from_str(&res.body)?)
+}
+
+/// Get branch names only if they changed since validators were received
+pub async fn get_branch_names_if_changed(
+	api_address: &str,
+	validators: &Validators,
+) -> std::result::Result<Fetched<BranchNames>, ManifestError> {
+	// INFO: reading a folder is cheap, always report as changed
+	if let Some(root) = local::root(api_address) {
+		return Ok(Fetched::Changed(
+			local::get_branch_names(&root)?,
+			Validators::default(),
+		));
+	}
+
+	let path = format!("{}/mods", api_address);
+
+	match get_if_changed(path, validators).await? {
+		Fetched::Changed(res, validators) => Ok(Fetched::Changed(
+			serde_json::from_str(&res.body)?,
+			validators,
+		)),
+		Fetched::NotModified => Ok(Fetched::NotModified),
+	}
+}
+
+/// Get the hoster's hex ed25519 public key used for signing branch manifests
+/// Only from the primary server, a mirror could hand out its own key for its own manifests
+pub async fn get_signing_key(api_address: &str) -> Result<String> {
+	let path = format!("{}/key", api_address);
+	let res = request(Method::GET, path)
 		.send()
 		.await?
-		.json::<BranchInfo>()
+		.error_for_status()?
+		.text()
 		.await?;
 
-	Ok(res)
+	Ok(res.trim().to_string())
+}
+
+/// Get branch manifest, checking its signature according to the signature policy
+/// Comes from the on-disk cache if it didn't change or the hoster is unreachable
+pub async fn get_mods_in_branch(
+	api_address: &str,
+	branch_name: &str,
+) -> std::result::Result<BranchInfo, ManifestError> {
+	if let Some(root) = local::root(api_address) {
+		check_local_policy()?;
+		return Ok(local::get_mods_in_branch(&root, branch_name)?);
+	}
+
+	let path = format!("{}/mods/{}", api_address, branch_name);
+	let res = get_cached(path).await?;
+
+	verify_manifest(api_address, res.body.as_bytes(), res.signature.as_deref()).await?;
+
+	Ok(serde_json::from_str(&res.body)?)
+}
+
+/// Get branch manifest only if it changed since validators were received
+pub async fn get_mods_in_branch_if_changed(
+	api_address: &str,
+	branch_name: &str,
+	validators: &Validators,
+) -> std::result::Result<Fetched<BranchInfo>, ManifestError> {
+	if let Some(root) = local::root(api_address) {
+		check_local_policy()?;
+		return Ok(Fetched::Changed(
+			local::get_mods_in_branch(&root, branch_name)?,
+			Validators::default(),
+		));
+	}
+
+	let path = format!("{}/mods/{}", api_address, branch_name);
+
+	match get_if_changed(path, validators).await? {
+		Fetched::Changed(res, validators) => {
+			verify_manifest(api_address, res.body.as_bytes(), res.signature.as_deref()).await?;
+			Ok(Fetched::Changed(
+				serde_json::from_str(&res.body)?,
+				validators,
+			))
+		}
+		Fetched::NotModified => Ok(Fetched::NotModified),
+	}
+}
+
+/// Get path of mod if main address is a local source
+pub fn local_mod_path(main_address: &str, branch_name: &str, file_name: &str) -> Option<PathBuf> {
+	local::mod_path(&local::root(main_address)?, branch_name, file_name)
 }
 
 pub async fn request_mod(
@@ -101,14 +707,44 @@ pub async fn request_mod(
 	file_name: &str,
 ) -> Result<Response> {
 	let path = format!("{}/mods/{}/{}", main_address, branch_name, file_name);
-	let res = get_client().get(path).send().await?;
+	let res = send(path, |p| request(Method::GET, p)).await?;
 
 	Ok(res)
 }
 
 pub async fn request_mod_zip(main_address: &str, branch_name: &str) -> Result<Response> {
 	let path = format!("{}/mods/{}", main_address, branch_name);
-	let res = get_client().get(path).send().await?;
+	let res = send(path, |p| request(Method::GET, p)).await?;
+
+	Ok(res)
+}
+
+/// Returns wether the server can send the branch's zip in parts (so it can be resumed)
+pub async fn mod_zip_supports_ranges(main_address: &str, branch_name: &str) -> Result<bool> {
+	if local::root(main_address).is_some() {
+		return Ok(false);
+	}
+
+	let path = format!("{}/mods/{}", main_address, branch_name);
+	let res = send(path, |p| request(Method::HEAD, p)).await?;
+
+	Ok(res
+		.headers()
+		.get(header::ACCEPT_RANGES)
+		.is_some_and(|v| v.as_bytes() == b"bytes"))
+}
+
+/// Request the branch's zip from start_byte to the end
+pub async fn request_mod_zip_range(
+	main_address: &str,
+	branch_name: &str,
+	start_byte: u64,
+) -> Result<Response> {
+	let path = format!("{}/mods/{}", main_address, branch_name);
+	let res = send(path, |p| {
+		request(Method::GET, p).header(header::RANGE, format!("bytes={}-", start_byte))
+	})
+	.await?;
 
 	Ok(res)
 }
@@ -0,0 +1,138 @@
+// Local folder (e.g. NAS, USB stick) or file:// url as source instead of a hoster
+//
+// Layout mirrors the hoster's:
+// - `<root>/<branch>/*.jar` required mods
+// - `<root>/<branch>/optional/*.jar` optional mods
+// - `<root>/<branch>/groups.json` optional mod groups and presets (can be missing)
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use reqwest::Url;
+use serde::Deserialize;
+
+use super::{BranchInfo, BranchNames, Mod, ModGroup, Preset, ZipFile};
+
+const OPTIONAL_DIR: &str = "optional";
+const GROUPS_FILE: &str = "groups.json";
+
+/// Same fields as the hoster's branch manifest
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Groups {
+	groups: Vec<ModGroup>,
+	presets: Vec<Preset>,
+}
+
+/// Get source's root folder if address is a local folder or file:// url
+/// Accepts api address too (root + "/api")
+pub fn root(address: &str) -> Option<PathBuf> {
+	let address = address.strip_suffix("/api").unwrap_or(address);
+
+	let path = if address.starts_with("file://") {
+		Url::parse(address).ok()?.to_file_path().ok()?
+	} else {
+		PathBuf::from(address)
+	};
+
+	// INFO: http addresses without scheme (e.g. example.com/minecraft) aren't local folders
+	path.is_dir().then_some(path)
+}
+
+fn is_jar(path: &Path) -> bool {
+	path.is_file()
+		&& path
+			.extension()
+			.and_then(|ext| ext.to_str())
+			.is_some_and(|ext| ext.eq_ignore_ascii_case("jar"))
+}
+
+/// Read in mods from folder
+fn read_mods(dir: &Path, is_optional: bool) -> std::io::Result<Vec<Mod>> {
+	let mut mods = Vec::new();
+
+	let entries = match dir.read_dir() {
+		Ok(entries) => entries,
+		// INFO: optional folder doesn't have to exist
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound && is_optional => return Ok(mods),
+		Err(err) => return Err(err),
+	};
+
+	for entry in entries.filter_map(Result::ok) {
+		let path = entry.path();
+		if !is_jar(&path) {
+			continue;
+		}
+
+		let metadata = entry.metadata()?;
+		let mod_date = metadata
+			.modified()
+			.ok()
+			.and_then(|v| v.duration_since(UNIX_EPOCH).ok())
+			.map(|v| v.as_secs_f64())
+			.unwrap_or_default();
+
+		mods.push(Mod {
+			name: entry.file_name().to_string_lossy().into_owned(),
+			mod_date,
+			size: metadata.len(),
+			is_optional,
+			hash: None,
+		});
+	}
+
+	Ok(mods)
+}
+
+/// Branches are the subfolders of root
+pub fn get_branch_names(root: &Path) -> std::io::Result<BranchNames> {
+	let mut branch_names: BranchNames = root
+		.read_dir()?
+		.filter_map(Result::ok)
+		.filter(|entry| entry.path().is_dir())
+		.map(|entry| entry.file_name().to_string_lossy().into_owned())
+		.collect();
+
+	branch_names.sort();
+
+	Ok(branch_names)
+}
+
+fn read_groups(branch_dir: &Path) -> std::io::Result<Groups> {
+	let json = match std::fs::read_to_string(branch_dir.join(GROUPS_FILE)) {
+		Ok(json) => json,
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Groups::default()),
+		Err(err) => return Err(err),
+	};
+
+	serde_json::from_str(&json)
+		.map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+pub fn get_mods_in_branch(root: &Path, branch_name: &str) -> std::io::Result<BranchInfo> {
+	let branch_dir = root.join(branch_name);
+
+	let mut mods = read_mods(&branch_dir, false)?;
+	mods.extend(read_mods(&branch_dir.join(OPTIONAL_DIR), true)?);
+	let groups = read_groups(&branch_dir)?;
+
+	// INFO: there is no zip, every mod is copied one by one
+	Ok(BranchInfo {
+		mods,
+		zip: ZipFile::default(),
+		groups: groups.groups,
+		presets: groups.presets,
+	})
+}
+
+/// Get path of a mod in branch, required or optional
+pub fn mod_path(root: &Path, branch_name: &str, file_name: &str) -> Option<PathBuf> {
+	let branch_dir = root.join(branch_name);
+
+	[
+		branch_dir.join(file_name),
+		branch_dir.join(OPTIONAL_DIR).join(file_name),
+	]
+	.into_iter()
+	.find(|v| v.is_file())
+}
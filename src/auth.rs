@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+
+use crate::tr;
+
+const KEYRING_SERVICE: &str = "minecraft-mod-syncer";
+
+/// How the hoster wants to be authenticated, stored in the profile (without the secret)
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum AuthKind {
+	/// HTTP basic auth, secret is the password
+	Basic { username: String },
+	/// Authorization: Bearer header, secret is the token
+	Bearer,
+	/// Custom header (e.g. X-Api-Key), secret is the header's value
+	Header { name: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct Credentials {
+	pub kind: AuthKind,
+	pub secret: String,
+}
+
+fn secrets_dir() -> PathBuf {
+	dirs::config_dir()
+		.expect("Couldnt access OS's default config dir")
+		.join("minecraft-mod-syncer")
+}
+
+/// Write a file only the current user can read
+/// The mode is given when the file is created, so it's never readable by others, not even for a moment
+fn write_private(path: &Path, contents: &[u8], create_new: bool) -> std::io::Result<()> {
+	let mut options = std::fs::OpenOptions::new();
+	options.write(true);
+	if create_new {
+		options.create_new(true);
+	} else {
+		options.create(true).truncate(true);
+	}
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::OpenOptionsExt;
+		options.mode(0o600);
+	}
+
+	let mut file = options.open(path)?;
+
+	// INFO: mode is only used for new files, restrict ones written before too (it's empty at this point)
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+	}
+
+	file.write_all(contents)
+}
+
+/// Get key for the secrets file, generates one if it doesn't exist yet
+/// The key is next to the secrets, this only keeps secrets out of profiles.json (and backups/screenshots of it)
+/// for machines without OS keyring
+fn get_file_key() -> std::io::Result<Key> {
+	let path = secrets_dir().join("secrets.key");
+
+	if let Ok(bytes) = std::fs::read(&path)
+		&& bytes.len() == 32
+	{
+		return Ok(*Key::from_slice(&bytes));
+	}
+
+	let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+
+	// INFO: broken key can't decrypt anything, replace it with a freshly created file
+	let _ = std::fs::remove_file(&path);
+	write_private(&path, &key, true)?;
+
+	Ok(key)
+}
+
+/// Read in encrypted secrets file (profile name -> hex nonce + ciphertext)
+fn load_secrets_file() -> HashMap<String, String> {
+	std::fs::read_to_string(secrets_dir().join("secrets.json"))
+		.ok()
+		.and_then(|json| serde_json::from_str(&json).ok())
+		.unwrap_or_default()
+}
+
+fn save_secrets_file(secrets: &HashMap<String, String>) -> std::io::Result<()> {
+	let json = serde_json::to_string(secrets).expect("Failed to convert secrets to json");
+	write_private(&secrets_dir().join("secrets.json"), json.as_bytes(), false)
+}
+
+fn file_get_secret(profile_name: &str) -> Option<String> {
+	let stored = load_secrets_file().remove(profile_name)?;
+	let bytes = hex::decode(stored).ok()?;
+
+	if bytes.len() < 12 {
+		return None;
+	}
+
+	let (nonce, ciphertext) = bytes.split_at(12);
+	let cipher = ChaCha20Poly1305::new(&get_file_key().ok()?);
+	let plain = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+
+	String::from_utf8(plain).ok()
+}
+
+fn file_set_secret(profile_name: &str, secret: &str) -> std::io::Result<()> {
+	let cipher = ChaCha20Poly1305::new(&get_file_key()?);
+	let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+	let ciphertext = cipher
+		.encrypt(&nonce, secret.as_bytes())
+		.map_err(|_| std::io::Error::other(tr!("alert.encrypt_secret")))?;
+
+	let mut stored = nonce.to_vec();
+	stored.extend(ciphertext);
+
+	let mut secrets = load_secrets_file();
+	secrets.insert(profile_name.to_string(), hex::encode(stored));
+	save_secrets_file(&secrets)
+}
+
+fn file_delete_secret(profile_name: &str) {
+	let mut secrets = load_secrets_file();
+	if secrets.remove(profile_name).is_some() {
+		let _ = save_secrets_file(&secrets);
+	}
+}
+
+/// Get profile's secret from OS keyring, or from the encrypted file if keyring isn't avaliable
+pub fn get_secret(profile_name: &str) -> Option<String> {
+	let keyring_secret =
+		keyring::Entry::new(KEYRING_SERVICE, profile_name).and_then(|entry| entry.get_password());
+
+	match keyring_secret {
+		Ok(secret) => Some(secret),
+		Err(_) => file_get_secret(profile_name),
+	}
+}
+
+/// Store profile's secret in OS keyring, or in the encrypted file if keyring isn't avaliable
+pub fn set_secret(profile_name: &str, secret: &str) -> std::io::Result<()> {
+	let keyring_res = keyring::Entry::new(KEYRING_SERVICE, profile_name)
+		.and_then(|entry| entry.set_password(secret));
+
+	match keyring_res {
+		Ok(_) => {
+			// INFO: don't leave old secret behind if keyring became avaliable since
+			file_delete_secret(profile_name);
+			Ok(())
+		}
+		Err(err) => {
+			println!("OS keyring unavaliable, using encrypted file: {}", err);
+			file_set_secret(profile_name, secret)
+		}
+	}
+}
+
+/// Remove profile's secret from everywhere
+pub fn delete_secret(profile_name: &str) {
+	if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, profile_name) {
+		let _ = entry.delete_credential();
+	}
+
+	file_delete_secret(profile_name);
+}
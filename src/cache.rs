@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::api::Mod;
+use crate::settings;
+use crate::tr;
+
+/// How mods are placed into the mods folder from the cache
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InstallMode {
+	/// Separate copy in every mods folder
+	#[default]
+	Copy,
+	/// Mods folders and the cache share the same file (same drive only)
+	/// Editing a mod in place changes the cached copy and every other mods folder's too
+	HardLink,
+	/// Copy-on-write clone (only on filesystems that support it, e.g. btrfs, APFS, ReFS)
+	Reflink,
+	/// Link pointing into the cache, evicting or clearing the cache breaks these mods
+	Symlink,
+}
+
+impl InstallMode {
+	pub const ALL: [InstallMode; 4] = [
+		InstallMode::Copy,
+		InstallMode::HardLink,
+		InstallMode::Reflink,
+		InstallMode::Symlink,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			InstallMode::Copy => "Copy",
+			InstallMode::HardLink => "Hard link",
+			InstallMode::Reflink => "Reflink",
+			InstallMode::Symlink => "Symlink",
+		}
+	}
+
+	/// Translated name for menus
+	pub fn label(&self) -> String {
+		tr!(match self {
+			InstallMode::Copy => "install_mode.copy",
+			InstallMode::HardLink => "install_mode.hard_link",
+			InstallMode::Reflink => "install_mode.reflink",
+			InstallMode::Symlink => "install_mode.symlink",
+		})
+	}
+}
+
+/// Cache won't grow above this, least recently used mods are evicted first (2 GB)
+pub const DEFAULT_SIZE_LIMIT: u64 = 2_000_000_000;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+struct CacheEntry {
+	size: u64,
+	/// Seconds since unix epoch
+	last_used: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+struct CacheIndex {
+	version: u8,
+	entries: HashMap<String, CacheEntry>,
+}
+
+impl CacheIndex {
+	fn new() -> Self {
+		Self {
+			version: 1,
+			entries: HashMap::new(),
+		}
+	}
+}
+
+struct Cache {
+	dir: PathBuf,
+	index: CacheIndex,
+	size_limit: u64,
+	/// Why the cache is turned off for this session (e.g. folder is on an unmounted drive)
+	disabled: Option<String>,
+}
+
+impl Cache {
+	fn index_path(&self) -> PathBuf {
+		self.dir.join("index.json")
+	}
+
+	fn total_size(&self) -> u64 {
+		self.index.entries.values().fold(0, |acc, x| acc + x.size)
+	}
+
+	async fn save(&self) {
+		let json =
+			serde_json::to_string(&self.index).expect("Failed to convert cache index to json");
+
+		if let Err(err) = tokio::fs::write(self.index_path(), json).await {
+			println!("Failed to write cache index: {}", err);
+		}
+	}
+
+	/// Remove least recently used entries until cache fits into size_limit
+	async fn evict(&mut self) {
+		let mut total_size = self.total_size();
+		if total_size <= self.size_limit {
+			return;
+		}
+
+		let mut entries: Vec<(String, CacheEntry)> =
+			self.index.entries.clone().into_iter().collect();
+		entries.sort_by_key(|e| e.1.last_used);
+
+		for (key, entry) in entries {
+			if total_size <= self.size_limit {
+				break;
+			}
+
+			let _ = tokio::fs::remove_file(self.dir.join(&key)).await;
+			self.index.entries.remove(&key);
+			total_size -= entry.size;
+		}
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+/// Key of a mod in the cache
+/// Uses content hash if server sent one, else name + size + modification date
+pub fn cache_key(mcmod: &Mod) -> String {
+	let key = match &mcmod.hash {
+		Some(hash) => format!("sha256-{}", hash),
+		None => format!("{}-{}-{}", mcmod.name, mcmod.size, mcmod.mod_date),
+	};
+
+	// INFO: key is used as file name, don't let server send paths
+	key.chars()
+		.map(|c| {
+			if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+				c
+			} else {
+				'_'
+			}
+		})
+		.collect()
+}
+
+/// Cache folder used when settings don't set one
+/// # Panics
+/// if platform isn't windows, linux or macos
+pub fn default_dir() -> PathBuf {
+	dirs::config_dir()
+		.expect("Couldnt access OS's default config dir")
+		.join("minecraft-mod-syncer")
+		.join("cache")
+}
+
+async fn load_index(dir: &Path) -> CacheIndex {
+	match tokio::fs::read_to_string(dir.join("index.json")).await {
+		Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+			println!("Cache index is corrupt, starting new one: {}", err);
+			CacheIndex::new()
+		}),
+		Err(_) => CacheIndex::new(),
+	}
+}
+
+/// Generate and get the cache
+/// Cache is disabled for the session if its folder couldn't be created
+/// # Panics
+/// if platform isn't windows, linux or macos
+async fn get_cache() -> &'static Mutex<Cache> {
+	static CACHE: OnceCell<Mutex<Cache>> = OnceCell::const_new();
+
+	CACHE
+		.get_or_init(|| async {
+			let settings = settings::get();
+			let dir = settings.cache_dir();
+
+			let disabled = match tokio::fs::create_dir_all(&dir).await {
+				Ok(_) => None,
+				Err(err) => {
+					println!("Couldn't create cache dir, cache is disabled: {}", err);
+					Some(format!("{} ({})", dir.display(), err))
+				}
+			};
+
+			Mutex::new(Cache {
+				index: match disabled {
+					Some(_) => CacheIndex::new(),
+					None => load_index(&dir).await,
+				},
+				dir,
+				size_limit: settings.cache_size_limit,
+				disabled,
+			})
+		})
+		.await
+}
+
+/// Returns why the cache is disabled for this session, None if it works
+pub async fn disabled_reason() -> Option<String> {
+	get_cache().await.lock().await.disabled.clone()
+}
+
+/// Use dir and size_limit from now on
+/// Mods in the previous folder stay there, but aren't used until it's set again
+pub async fn configure(dir: PathBuf, size_limit: u64) -> std::io::Result<()> {
+	let mut cache = get_cache().await.lock().await;
+
+	if cache.dir != dir || cache.disabled.is_some() {
+		tokio::fs::create_dir_all(&dir).await?;
+		cache.index = load_index(&dir).await;
+		cache.dir = dir;
+		cache.disabled = None;
+	}
+
+	cache.size_limit = size_limit;
+	cache.evict().await;
+	cache.save().await;
+
+	Ok(())
+}
+
+/// Get path of a cached mod and mark it as used
+pub async fn get(mcmod: &Mod) -> Option<PathBuf> {
+	let mut cache = get_cache().await.lock().await;
+	if cache.disabled.is_some() {
+		return None;
+	}
+	let key = cache_key(mcmod);
+
+	let path = cache.dir.join(&key);
+	let entry = cache.index.entries.get_mut(&key)?;
+
+	if !path.is_file() {
+		cache.index.entries.remove(&key);
+		cache.save().await;
+		return None;
+	}
+
+	entry.last_used = now();
+	cache.save().await;
+
+	Some(path)
+}
+
+/// Get path of a cached mod without marking it as used
+pub async fn peek(mcmod: &Mod) -> Option<PathBuf> {
+	let cache = get_cache().await.lock().await;
+	let key = cache_key(mcmod);
+
+	let path = cache.dir.join(&key);
+	(cache.index.entries.contains_key(&key) && path.is_file()).then_some(path)
+}
+
+/// Returns wether mod is in cache, without marking it as used
+pub async fn contains(mcmod: &Mod) -> bool {
+	let cache = get_cache().await.lock().await;
+	let key = cache_key(mcmod);
+
+	cache.index.entries.contains_key(&key) && cache.dir.join(&key).is_file()
+}
+
+#[cfg(unix)]
+fn symlink(src: &Path, dest: &Path) -> std::io::Result<()> {
+	std::os::unix::fs::symlink(src, dest)
+}
+
+#[cfg(windows)]
+fn symlink(src: &Path, dest: &Path) -> std::io::Result<()> {
+	std::os::windows::fs::symlink_file(src, dest)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(_src: &Path, _dest: &Path) -> std::io::Result<()> {
+	Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+/// Place src to dest with mode, falls back to copying if mode isn't possible
+/// (e.g. hard link to other drive, reflink on ext4, symlink without privileges on windows)
+/// This blocks, run it with spawn_blocking
+fn link_or_copy_blocking(src: &Path, dest: &Path, mode: InstallMode) -> std::io::Result<()> {
+	// INFO: links fail if dest exists
+	let _ = std::fs::remove_file(dest);
+
+	let res = match mode {
+		InstallMode::Copy => return std::fs::copy(src, dest).map(|_| ()),
+		InstallMode::HardLink => std::fs::hard_link(src, dest),
+		InstallMode::Reflink => reflink_copy::reflink(src, dest),
+		InstallMode::Symlink => symlink(&src.canonicalize()?, dest),
+	};
+
+	if let Err(err) = res {
+		println!("{} failed, copying instead: {}", mode.name(), err);
+		std::fs::copy(src, dest)?;
+	}
+
+	Ok(())
+}
+
+/// Place src to dest without blocking the async runtime
+async fn link_or_copy(src: &Path, dest: &Path, mode: InstallMode) -> std::io::Result<()> {
+	let src = src.to_path_buf();
+	let dest = dest.to_path_buf();
+	tokio::task::spawn_blocking(move || link_or_copy_blocking(&src, &dest, mode))
+		.await
+		.map_err(std::io::Error::other)?
+}
+
+/// Install cached mod at path (from get) to dest
+pub async fn install(path: &Path, dest: &Path, mode: InstallMode) -> std::io::Result<()> {
+	link_or_copy(path, dest, mode).await
+}
+
+/// Drop a mod from the cache, e.g. because it's broken
+pub async fn remove(mcmod: &Mod) {
+	let mut cache = get_cache().await.lock().await;
+	let key = cache_key(mcmod);
+
+	if cache.index.entries.remove(&key).is_some() {
+		let _ = tokio::fs::remove_file(cache.dir.join(&key)).await;
+		cache.save().await;
+	}
+}
+
+/// Put downloaded mod from src into the cache
+/// src has to be validated against the mod's size and hash first, the key is made from them
+/// Hard link mode shares the file with the cache instead of copying it
+pub async fn insert(mcmod: &Mod, src: &Path, mode: InstallMode) {
+	let mut cache = get_cache().await.lock().await;
+	let key = cache_key(mcmod);
+
+	if cache.disabled.is_some() || mcmod.size > cache.size_limit {
+		return;
+	}
+
+	let mode = match mode {
+		InstallMode::HardLink => InstallMode::HardLink,
+		_ => InstallMode::Copy,
+	};
+
+	if let Err(err) = link_or_copy(src, &cache.dir.join(&key), mode).await {
+		println!("Failed to cache '{}': {}", mcmod.name, err);
+		return;
+	}
+
+	cache.index.entries.insert(
+		key,
+		CacheEntry {
+			size: mcmod.size,
+			last_used: now(),
+		},
+	);
+
+	cache.evict().await;
+	cache.save().await;
+}
+
+/// Size of every cached mod in bytes
+pub async fn size() -> u64 {
+	get_cache().await.lock().await.total_size()
+}
+
+/// Remove every mod from the cache
+pub async fn clear() {
+	let mut cache = get_cache().await.lock().await;
+	if cache.disabled.is_some() {
+		return;
+	}
+
+	for key in cache.index.entries.keys() {
+		let _ = tokio::fs::remove_file(cache.dir.join(key)).await;
+	}
+
+	cache.index.entries.clear();
+	cache.save().await;
+}
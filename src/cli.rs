@@ -0,0 +1,59 @@
+use crate::tr;
+
+/// Options given on the command line
+#[derive(Debug, Default, Clone)]
+pub struct Args {
+	/// Bytes per second
+	pub limit_rate: Option<u64>,
+	/// Name of one of the branch's presets, applied when its mods are first listed
+	pub preset: Option<String>,
+	/// Print the sync plan without the gui
+	pub dry_run: bool,
+	/// Print the dry run's plan as JSON
+	pub json: bool,
+}
+
+/// Parse command line arguments, the language has to be set before
+/// Exits the program on --help, --version or invalid arguments
+pub fn parse_args() -> Args {
+	let mut args = Args::default();
+	let mut iter = std::env::args().skip(1);
+
+	while let Some(arg) = iter.next() {
+		match arg.as_str() {
+			"--limit-rate" => {
+				let value = iter.next().and_then(|v| v.parse::<u64>().ok());
+				match value {
+					Some(kbps) => args.limit_rate = Some(kbps * 1000),
+					None => exit_with_error(&tr!("cli.limit_rate_number")),
+				}
+			}
+			"--preset" => match iter.next() {
+				Some(name) => args.preset = Some(name),
+				None => exit_with_error(&tr!("cli.preset_name")),
+			},
+			"--dry-run" => args.dry_run = true,
+			"--json" => args.json = true,
+			"-h" | "--help" => {
+				println!("{}", tr!("cli.help"));
+				std::process::exit(0);
+			}
+			"-V" | "--version" => {
+				println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+				std::process::exit(0);
+			}
+			_ => exit_with_error(&tr!("cli.unknown_argument", argument = arg)),
+		}
+	}
+
+	if args.json && !args.dry_run {
+		exit_with_error(&tr!("cli.json_needs_dry_run"));
+	}
+
+	args
+}
+
+fn exit_with_error(msg: &str) -> ! {
+	eprintln!("{}\n\n{}", msg, tr!("cli.help"));
+	std::process::exit(2);
+}
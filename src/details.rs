@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use crate::api::Mod;
+use crate::{cache, jar, syncer, tr, utils};
+
+/// What the details panel shows about one mod
+#[derive(Debug, Clone, Default)]
+pub struct ModDetails {
+	pub text: String,
+	/// Raw png bytes
+	pub icon: Option<Vec<u8>>,
+	pub homepage: Option<String>,
+}
+
+/// Collect everything known about a mod
+/// remote: the branch's entry, local: file in mods folder, source: file in a local folder source
+pub async fn collect(
+	name: &str,
+	remote: Option<&Mod>,
+	local: Option<PathBuf>,
+	source: Option<PathBuf>,
+) -> ModDetails {
+	let mut lines = vec![tr!("details.file", name = name)];
+
+	let state = match (remote, local.as_ref()) {
+		(Some(remote), Some(local)) if syncer::is_outdated(local, remote) => {
+			tr!("details.state_outdated")
+		}
+		(Some(_), Some(_)) => tr!("details.state_installed"),
+		(Some(_), None) => tr!("details.state_remote"),
+		(None, _) => tr!("details.state_local"),
+	};
+	lines.push(tr!("details.state", state = state));
+
+	let local_meta = local.as_ref().and_then(|v| v.metadata().ok());
+	let (size, date) = match remote {
+		Some(remote) => (Some(remote.size), Some(remote.mod_date)),
+		None => (
+			local_meta.as_ref().map(|v| v.len()),
+			local_meta
+				.as_ref()
+				.and_then(|v| v.modified().ok())
+				.and_then(|v| v.duration_since(UNIX_EPOCH).ok())
+				.map(|v| v.as_secs_f64()),
+		),
+	};
+	if let Some(size) = size {
+		lines.push(tr!(
+			"details.size",
+			size = utils::readable_size(size as f64)
+		));
+	}
+	if let Some(date) = date.filter(|v| *v > 0.0) {
+		lines.push(tr!("details.date", date = utils::readable_date(date)));
+	}
+	if let Some(remote) = remote {
+		lines.push(if remote.is_optional {
+			tr!("details.optional")
+		} else {
+			tr!("details.required")
+		});
+	}
+
+	// INFO: installed file first, then the cache, remote only jars aren't downloaded for this
+	let jar_path = match (local, remote) {
+		(Some(local), _) => Some(local),
+		(None, Some(remote)) => cache::peek(remote).await.or(source),
+		(None, None) => None,
+	};
+	let metadata = match jar_path {
+		Some(path) => jar::read_metadata_async(&path).await,
+		None => None,
+	};
+
+	let metadata = match metadata {
+		Some(metadata) => metadata,
+		None => {
+			lines.push(String::new());
+			lines.push(tr!("details.no_metadata"));
+			return ModDetails {
+				text: lines.join("\n"),
+				..Default::default()
+			};
+		}
+	};
+
+	lines.push(String::new());
+	if let Some(mod_name) = metadata.name.as_ref() {
+		lines.push(tr!("details.name", name = mod_name));
+	}
+	if let Some(id) = metadata.id.as_ref() {
+		lines.push(tr!("details.id", id = id));
+	}
+	if let Some(version) = metadata.version.as_ref() {
+		lines.push(tr!("details.version", version = version));
+	}
+	if !metadata.authors.is_empty() {
+		lines.push(tr!(
+			"details.authors",
+			count = metadata.authors.len(),
+			authors = metadata.authors.join(", ")
+		));
+	}
+	if let Some(description) = metadata.description.as_ref() {
+		lines.push(String::new());
+		lines.push(description.clone());
+	}
+
+	ModDetails {
+		text: lines.join("\n"),
+		icon: metadata.icon,
+		homepage: metadata.homepage,
+	}
+}
@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::sync::{LazyLock, RwLock};
+
+use serde::Deserialize;
+
+/// Returns wether count takes the "one" form of plural messages
+type PluralRule = fn(u64) -> bool;
+
+/// Catalogs are compiled in, so translations can't go missing next to the exe
+/// Each language comes with its own plural rule
+const CATALOGS: [(&str, &str, PluralRule); 3] = [
+	("en", include_str!("../locales/en.json"), only_one),
+	("hu", include_str!("../locales/hu.json"), only_one),
+	("de", include_str!("../locales/de.json"), only_one),
+];
+
+const FALLBACK_LANGUAGE: &str = "en";
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Message {
+	Text(String),
+	Plural { one: String, other: String },
+}
+
+/// English, German and Hungarian: 1 is singular, everything else (0 too) is plural
+// INFO: hungarian nouns stay singular after numbers, its catalog uses the same text for both forms
+fn only_one(count: u64) -> bool {
+	count == 1
+}
+
+struct Catalog {
+	messages: HashMap<String, Message>,
+	is_singular: PluralRule,
+}
+
+impl Catalog {
+	fn new() -> Self {
+		Self {
+			messages: HashMap::new(),
+			is_singular: only_one,
+		}
+	}
+
+	fn get(&self, key: &str, count: Option<u64>) -> Option<String> {
+		let text = match self.messages.get(key)? {
+			Message::Text(text) => text,
+			Message::Plural { one, other } => {
+				if count.is_some_and(self.is_singular) {
+					one
+				} else {
+					other
+				}
+			}
+		};
+
+		Some(text.clone())
+	}
+}
+
+fn load_catalog(code: &str) -> Catalog {
+	match CATALOGS.iter().find(|v| v.0 == code) {
+		Some((_, json, is_singular)) => Catalog {
+			messages: serde_json::from_str(json).unwrap_or_else(|err| {
+				println!("Broken '{}' catalog: {}", code, err);
+				HashMap::new()
+			}),
+			is_singular: *is_singular,
+		},
+		None => Catalog::new(),
+	}
+}
+
+static FALLBACK: LazyLock<Catalog> = LazyLock::new(|| load_catalog(FALLBACK_LANGUAGE));
+
+static CURRENT: LazyLock<RwLock<Catalog>> = LazyLock::new(|| RwLock::new(Catalog::new()));
+
+/// Use language's catalog from now on, unknown codes fall back to English
+pub fn set_language(code: &str) {
+	*CURRENT.write().unwrap() = load_catalog(code);
+}
+
+/// Key -> text of every message without placeholders in the current language
+fn plain_texts() -> BTreeMap<String, String> {
+	FALLBACK
+		.messages
+		.iter()
+		.filter(|v| matches!(v.1, Message::Text(_)))
+		.map(|(key, _)| (key.clone(), tr(key, None, &[])))
+		.filter(|v| !v.1.contains('{'))
+		.collect()
+}
+
+/// Texts of the previous and the current language, to switch already built labels over
+pub struct Relabel {
+	old: BTreeMap<String, String>,
+	new: BTreeMap<String, String>,
+}
+
+impl Relabel {
+	/// New text of label if it was made from a key starting with one of prefixes (any key if empty)
+	pub fn text(&self, label: &str, prefixes: &[&str]) -> Option<String> {
+		self.old
+			.iter()
+			.filter(|(key, _)| prefixes.is_empty() || prefixes.iter().any(|v| key.starts_with(v)))
+			.find(|(_, text)| *text == label)
+			.and_then(|(key, _)| self.new.get(key))
+			.cloned()
+	}
+
+	/// New text of one item of a "a|b|c" message (e.g. choice entries)
+	pub fn item(&self, label: &str) -> Option<String> {
+		self.old
+			.iter()
+			.filter(|(_, text)| text.contains('|'))
+			.find_map(|(key, text)| {
+				let i = text.split('|').position(|v| v == label)?;
+				self.new.get(key)?.split('|').nth(i).map(String::from)
+			})
+	}
+}
+
+/// Use language's catalog from now on, returns what built labels have to be changed to
+pub fn switch_language(code: &str) -> Relabel {
+	let old = plain_texts();
+	set_language(code);
+
+	Relabel {
+		old,
+		new: plain_texts(),
+	}
+}
+
+/// Look up key and fill in {name} placeholders (and {count} if given)
+/// # Fallback
+/// current language -> English -> the key itself
+pub fn tr(key: &str, count: Option<u64>, args: &[(&str, &dyn Display)]) -> String {
+	let current = CURRENT.read().unwrap();
+
+	// INFO: English texts use English plural rule even when they stand in for a missing translation
+	let mut text = current
+		.get(key, count)
+		.or_else(|| FALLBACK.get(key, count))
+		.unwrap_or_else(|| key.to_string());
+
+	if let Some(count) = count {
+		text = text.replace("{count}", &count.to_string());
+	}
+	for (name, value) in args {
+		text = text.replace(&format!("{{{}}}", name), &value.to_string());
+	}
+
+	text
+}
+
+/// Translate a catalog key
+/// ```ignore
+/// tr!("main.download");
+/// tr!("alert.profile_exists", name = name);
+/// tr!("download.files", count = total, done = count);
+/// ```
+#[macro_export]
+macro_rules! tr {
+	($key:expr) => {
+		$crate::i18n::tr($key, None, &[])
+	};
+	($key:expr, count = $count:expr $(, $name:ident = $value:expr)* $(,)?) => {
+		$crate::i18n::tr(
+			$key,
+			Some($count as u64),
+			&[$((stringify!($name), &$value as &dyn std::fmt::Display)),*],
+		)
+	};
+	($key:expr $(, $name:ident = $value:expr)+ $(,)?) => {
+		$crate::i18n::tr(
+			$key,
+			None,
+			&[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+		)
+	};
+}
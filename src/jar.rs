@@ -0,0 +1,258 @@
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+use crate::tr;
+
+/// Check that jar is complete: size and sha256 match the hoster's, zip is readable and every entry passes CRC check
+/// Expected size of 0 means the hoster doesn't know it, neither do older servers know the hash
+/// This blocks, run it with spawn_blocking
+pub fn validate(
+	path: &Path,
+	expected_size: u64,
+	expected_hash: Option<&str>,
+) -> Result<(), String> {
+	let mut file = std::fs::File::open(path).map_err(|e| tr!("jar.open", error = e))?;
+	let size = file.metadata().map(|m| m.len()).unwrap_or_default();
+
+	if expected_size != 0 && size != expected_size {
+		return Err(tr!("jar.size", size = size, expected = expected_size));
+	}
+
+	if let Some(expected_hash) = expected_hash {
+		let mut hasher = Sha256::new();
+		std::io::copy(&mut file, &mut hasher).map_err(|e| tr!("jar.read", error = e))?;
+		let hash = hex::encode(hasher.finalize());
+
+		if !hash.eq_ignore_ascii_case(expected_hash.trim()) {
+			return Err(tr!("jar.hash", hash = hash, expected = expected_hash));
+		}
+
+		file.rewind().map_err(|e| tr!("jar.read", error = e))?;
+	}
+
+	let mut archive =
+		ZipArchive::new(BufReader::new(file)).map_err(|e| tr!("jar.not_zip", error = e))?;
+
+	for i in 0..archive.len() {
+		let mut entry = archive
+			.by_index(i)
+			.map_err(|e| tr!("jar.broken_entry", entry = format!("#{}", i), error = e))?;
+
+		// INFO: zip checks the CRC when the entry is read to its end
+		std::io::copy(&mut entry, &mut std::io::sink())
+			.map_err(|e| tr!("jar.broken_entry", entry = entry.name(), error = e))?;
+	}
+
+	Ok(())
+}
+
+/// Validate jar without blocking the async runtime
+pub async fn validate_async(
+	path: &Path,
+	expected_size: u64,
+	expected_hash: Option<&str>,
+) -> Result<(), String> {
+	let path = path.to_path_buf();
+	let expected_hash = expected_hash.map(String::from);
+	tokio::task::spawn_blocking(move || validate(&path, expected_size, expected_hash.as_deref()))
+		.await
+		.map_err(|e| e.to_string())?
+}
+
+/// What a jar tells about itself, every loader names these differently
+#[derive(Debug, Clone, Default)]
+pub struct ModMetadata {
+	pub id: Option<String>,
+	pub name: Option<String>,
+	pub version: Option<String>,
+	pub authors: Vec<String>,
+	pub description: Option<String>,
+	pub homepage: Option<String>,
+	/// Raw png bytes
+	pub icon: Option<Vec<u8>>,
+}
+
+type Archive = ZipArchive<BufReader<std::fs::File>>;
+
+fn read_entry(archive: &mut Archive, name: &str) -> Option<Vec<u8>> {
+	let mut entry = archive.by_name(name.trim_start_matches('/')).ok()?;
+	let mut buf = Vec::new();
+	entry.read_to_end(&mut buf).ok()?;
+	Some(buf)
+}
+
+fn read_string(archive: &mut Archive, name: &str) -> Option<String> {
+	read_entry(archive, name).map(|v| String::from_utf8_lossy(&v).to_string())
+}
+
+fn json_str(value: &Value, key: &str) -> Option<String> {
+	value
+		.get(key)
+		.and_then(|v| v.as_str())
+		.map(|v| v.trim().to_string())
+		.filter(|v| !v.is_empty())
+}
+
+/// Authors are either plain strings or objects with a name
+fn json_people(value: Option<&Value>) -> Vec<String> {
+	match value {
+		Some(Value::Array(people)) => people
+			.iter()
+			.filter_map(|v| match v {
+				Value::String(name) => Some(name.clone()),
+				_ => json_str(v, "name"),
+			})
+			.collect(),
+		Some(Value::Object(people)) => people.keys().cloned().collect(),
+		Some(Value::String(name)) => vec![name.clone()],
+		_ => Vec::new(),
+	}
+}
+
+/// Icon is a path or an object of size -> path, biggest wins
+fn json_icon(value: Option<&Value>) -> Option<String> {
+	match value? {
+		Value::String(path) => Some(path.clone()),
+		Value::Object(sizes) => sizes
+			.iter()
+			.max_by_key(|(size, _)| size.parse::<u32>().unwrap_or_default())
+			.and_then(|(_, path)| path.as_str())
+			.map(String::from),
+		_ => None,
+	}
+}
+
+fn fabric(json: &str) -> Option<(ModMetadata, Option<String>)> {
+	let value: Value = serde_json::from_str(json).ok()?;
+
+	let metadata = ModMetadata {
+		id: json_str(&value, "id"),
+		name: json_str(&value, "name"),
+		version: json_str(&value, "version"),
+		authors: json_people(value.get("authors")),
+		description: json_str(&value, "description"),
+		homepage: value.get("contact").and_then(|v| json_str(v, "homepage")),
+		icon: None,
+	};
+
+	Some((metadata, json_icon(value.get("icon"))))
+}
+
+fn quilt(json: &str) -> Option<(ModMetadata, Option<String>)> {
+	let value: Value = serde_json::from_str(json).ok()?;
+	let loader = value.get("quilt_loader")?;
+	let meta = loader.get("metadata").cloned().unwrap_or_default();
+
+	let metadata = ModMetadata {
+		id: json_str(loader, "id"),
+		name: json_str(&meta, "name"),
+		version: json_str(loader, "version"),
+		authors: json_people(meta.get("contributors")),
+		description: json_str(&meta, "description"),
+		homepage: meta.get("contact").and_then(|v| json_str(v, "homepage")),
+		icon: None,
+	};
+
+	Some((metadata, json_icon(meta.get("icon"))))
+}
+
+/// Forge and NeoForge, ${file.jarVersion} is filled from the manifest
+fn forge(toml_text: &str, archive: &mut Archive) -> Option<(ModMetadata, Option<String>)> {
+	let table: toml::Table = toml_text.parse().ok()?;
+	let first = table.get("mods")?.as_array()?.first()?.as_table()?;
+
+	let toml_str = |table: &toml::Table, key: &str| {
+		table
+			.get(key)
+			.and_then(|v| v.as_str())
+			.map(|v| v.trim().to_string())
+			.filter(|v| !v.is_empty())
+	};
+
+	let mut version = toml_str(first, "version");
+	if version.as_deref() == Some("${file.jarVersion}") {
+		version = read_string(archive, "META-INF/MANIFEST.MF").and_then(|manifest| {
+			manifest
+				.lines()
+				.find_map(|v| v.strip_prefix("Implementation-Version:"))
+				.map(|v| v.trim().to_string())
+		});
+	}
+
+	let authors = toml_str(first, "authors")
+		.or_else(|| toml_str(&table, "authors"))
+		.map(|v| v.split(',').map(|v| v.trim().to_string()).collect())
+		.unwrap_or_default();
+
+	let metadata = ModMetadata {
+		id: toml_str(first, "modId"),
+		name: toml_str(first, "displayName"),
+		version,
+		authors,
+		description: toml_str(first, "description"),
+		homepage: toml_str(first, "displayURL").or_else(|| toml_str(&table, "displayURL")),
+		icon: None,
+	};
+	let icon = toml_str(first, "logoFile").or_else(|| toml_str(&table, "logoFile"));
+
+	Some((metadata, icon))
+}
+
+/// Old forge, list of mods or {"modList": [...]}
+fn mcmod_info(json: &str) -> Option<(ModMetadata, Option<String>)> {
+	let value: Value = serde_json::from_str(json).ok()?;
+	let first = match &value {
+		Value::Array(mods) => mods.first()?,
+		_ => value.get("modList")?.as_array()?.first()?,
+	};
+
+	let metadata = ModMetadata {
+		id: json_str(first, "modid"),
+		name: json_str(first, "name"),
+		version: json_str(first, "version"),
+		authors: json_people(first.get("authorList").or_else(|| first.get("authors"))),
+		description: json_str(first, "description"),
+		homepage: json_str(first, "url"),
+		icon: None,
+	};
+
+	Some((metadata, json_str(first, "logoFile")))
+}
+
+/// Read mod loader metadata of a jar, None if it has none this knows
+/// This blocks, run it with spawn_blocking
+pub fn read_metadata(path: &Path) -> Option<ModMetadata> {
+	let file = std::fs::File::open(path).ok()?;
+	let mut archive = ZipArchive::new(BufReader::new(file)).ok()?;
+
+	let found = if let Some(json) = read_string(&mut archive, "fabric.mod.json") {
+		fabric(&json)
+	} else if let Some(json) = read_string(&mut archive, "quilt.mod.json") {
+		quilt(&json)
+	} else if let Some(text) = read_string(&mut archive, "META-INF/neoforge.mods.toml")
+		.or_else(|| read_string(&mut archive, "META-INF/mods.toml"))
+	{
+		forge(&text, &mut archive)
+	} else if let Some(json) = read_string(&mut archive, "mcmod.info") {
+		mcmod_info(&json)
+	} else {
+		None
+	};
+
+	let (mut metadata, icon_path) = found?;
+	metadata.icon = icon_path.and_then(|v| read_entry(&mut archive, &v));
+
+	Some(metadata)
+}
+
+pub async fn read_metadata_async(path: &Path) -> Option<ModMetadata> {
+	let path = path.to_path_buf();
+	tokio::task::spawn_blocking(move || read_metadata(&path))
+		.await
+		.ok()
+		.flatten()
+}
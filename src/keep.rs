@@ -0,0 +1,196 @@
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{jar, syncer, tr, utils};
+
+const SECS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// What a keep rule's pattern is matched against
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleTarget {
+	/// Glob, e.g. journeymap-*.jar
+	#[default]
+	FileName,
+	/// Id from the jar's metadata, survives the file being renamed by an update
+	ModId,
+}
+
+/// Mods in the to delete list matching a rule are kept (unchecked) by default
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct KeepRule {
+	pub pattern: String,
+	pub target: RuleTarget,
+	/// None for every branch
+	pub branch: Option<String>,
+	/// Only files at most this big, in bytes
+	pub max_size: Option<u64>,
+	/// Only files changed in the last this many days
+	pub max_age_days: Option<u64>,
+}
+
+impl KeepRule {
+	/// Rule the to delete list makes when a mod is unchecked
+	pub fn exact(name: &str, branch: &str) -> Self {
+		Self {
+			pattern: name.to_string(),
+			target: RuleTarget::FileName,
+			branch: Some(branch.to_string()),
+			..Default::default()
+		}
+	}
+
+	fn is_exact(&self, name: &str, branch: &str) -> bool {
+		*self == Self::exact(name, branch)
+	}
+
+	/// mod_id is only called for mod id rules, reading the jar is slow
+	fn matches(
+		&self,
+		branch: &str,
+		path: &Path,
+		mod_id: &mut impl FnMut() -> Option<String>,
+	) -> bool {
+		if self.branch.as_ref().is_some_and(|v| v != branch) {
+			return false;
+		}
+
+		let is_match = match self.target {
+			RuleTarget::FileName => {
+				let name = path
+					.file_name()
+					.map(|v| v.to_string_lossy().to_lowercase())
+					.unwrap_or_default();
+				utils::glob_match(&self.pattern.trim().to_lowercase(), &name)
+			}
+			RuleTarget::ModId => mod_id().is_some_and(|v| v == self.pattern.trim()),
+		};
+		if !is_match {
+			return false;
+		}
+
+		if self.max_size.is_none() && self.max_age_days.is_none() {
+			return true;
+		}
+
+		let (size, date) = syncer::size_and_date(path);
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|v| v.as_secs_f64())
+			.unwrap_or_default();
+
+		self.max_size.is_none_or(|v| size <= v)
+			&& self
+				.max_age_days
+				.is_none_or(|v| now - date <= v as f64 * SECS_PER_DAY)
+	}
+}
+
+impl fmt::Display for KeepRule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut parts = vec![match self.target {
+			RuleTarget::FileName => tr!("keep.file_name"),
+			RuleTarget::ModId => tr!("keep.mod_id"),
+		}];
+
+		parts.push(match self.branch.as_ref() {
+			Some(branch) => tr!("keep.in_branch", branch = branch),
+			None => tr!("keep.all_branches"),
+		});
+		if let Some(max_size) = self.max_size {
+			parts.push(tr!(
+				"keep.max_size",
+				size = utils::readable_size(max_size as f64)
+			));
+		}
+		if let Some(max_age_days) = self.max_age_days {
+			parts.push(tr!("keep.max_age", count = max_age_days));
+		}
+
+		write!(f, "{} ({})", self.pattern, parts.join(", "))
+	}
+}
+
+/// Returns wether any rule keeps the mod at path in branch
+pub fn is_kept(rules: &[KeepRule], branch: &str, path: &Path) -> bool {
+	// INFO: read at most once, and only if a mod id rule gets that far
+	let mut cached_id = None;
+	let mut mod_id = || {
+		cached_id
+			.get_or_insert_with(|| jar::read_metadata(path).and_then(|v| v.id))
+			.clone()
+	};
+
+	rules.iter().any(|v| v.matches(branch, path, &mut mod_id))
+}
+
+/// Make name kept (or not) in branch like the to delete list's checkboxes do
+/// Other rules aren't touched, so a pattern can still keep name
+pub fn set_kept(rules: &mut Vec<KeepRule>, name: &str, branch: &str, kept: bool) {
+	let index = rules.iter().position(|v| v.is_exact(name, branch));
+
+	match (kept, index) {
+		(true, None) => rules.push(KeepRule::exact(name, branch)),
+		(false, Some(index)) => {
+			rules.remove(index);
+		}
+		_ => {}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn file_name_rules_ignore_case() {
+		let rules = vec![KeepRule {
+			pattern: "JourneyMap-*.jar".to_string(),
+			..Default::default()
+		}];
+
+		assert!(is_kept(
+			&rules,
+			"main",
+			Path::new("mods/journeymap-5.9.jar")
+		));
+		assert!(!is_kept(&rules, "main", Path::new("mods/xaeros-map.jar")));
+	}
+
+	#[test]
+	fn branch_scoped_rules() {
+		let rules = vec![KeepRule::exact("mod.jar", "main")];
+
+		assert!(is_kept(&rules, "main", Path::new("mod.jar")));
+		assert!(!is_kept(&rules, "test", Path::new("mod.jar")));
+	}
+
+	#[test]
+	fn set_kept_is_idempotent() {
+		let mut rules = Vec::new();
+
+		set_kept(&mut rules, "mod.jar", "main", true);
+		set_kept(&mut rules, "mod.jar", "main", true);
+		assert_eq!(rules, vec![KeepRule::exact("mod.jar", "main")]);
+
+		set_kept(&mut rules, "mod.jar", "main", false);
+		set_kept(&mut rules, "mod.jar", "main", false);
+		assert!(rules.is_empty());
+	}
+
+	#[test]
+	fn set_kept_leaves_patterns_alone() {
+		let pattern = KeepRule {
+			pattern: "*.jar".to_string(),
+			..Default::default()
+		};
+		let mut rules = vec![pattern.clone()];
+
+		set_kept(&mut rules, "mod.jar", "main", false);
+		assert_eq!(rules, vec![pattern]);
+	}
+}
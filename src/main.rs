@@ -4,34 +4,58 @@ use std::{
 	collections::{HashMap, HashSet},
 	ops::{Deref, DerefMut},
 	path::{Path, PathBuf},
-	sync::Arc,
+	sync::{
+		Arc,
+		atomic::{AtomicBool, Ordering},
+	},
 };
 
-use fltk::{browser::CheckBrowser, prelude::*, *};
-use lazy_static::lazy_static;
+use fltk::{prelude::*, *};
 use semver::Version;
 use tokio::sync::{Mutex, RwLock};
 
 use crate::api::BranchInfo;
+use crate::auth::{AuthKind, Credentials};
+use crate::cache::InstallMode;
+use crate::network::NetworkSettings;
+use crate::signing::{ManifestError, SignaturePolicy};
 
 mod api;
+mod auth;
+mod cache;
+mod cli;
+mod details;
+mod i18n;
+mod jar;
+mod keep;
+mod network;
+mod planner;
+mod preview;
 mod profiles;
+mod scanner;
+mod settings;
+mod signing;
 mod syncer;
+mod theme;
+mod throttle;
 mod utils;
+mod watcher;
+mod widgets;
 
 #[derive(Debug, Default, Clone)]
 pub struct AppState {
 	server_api_address: Option<String>,
 	server_main_address: Option<String>,
 	branch_name: Option<String>,
+	branch_names: Vec<String>,
 	mods_path: Option<PathBuf>,
 	branch_info: Option<BranchInfo>,
 	to_download_names: HashMap<String, bool>,
 	to_delete_names: HashMap<String, bool>,
 	profile_name: Option<String>,
+	install_mode: InstallMode,
 }
 
-
 #[derive(Debug, Clone)]
 pub enum Events {
 	// Gui events
@@ -42,12 +66,36 @@ pub enum Events {
 	GetMods,
 	ModsResult(BranchInfo),
 	ModsError(String),
+	LoginRequired,
+	TrustKey(String),
+	LoginSubmit,
+	LoginCancel,
 	PathBrowse,
 	PathSet,
 	DownloadListUpdate,
 	DeleteListUpdate,
+	FilterChanged,
+	SortChanged,
+	SelectOptionals(bool),
+	AskNewOptionals(Vec<String>),
+	PresetChosen,
+	ApplyPreset(String),
+	ShowModDetails(String),
+	ModDetails(details::ModDetails),
 	Download,
+	Preview,
+	PreviewResult(preview::SyncPlan),
+	PreviewExport(preview::ExportFormat),
+	PreviewConfirm,
+	PreviewCancel,
 	Alert(String),
+	Message(String),
+	WatchChanges {
+		changes: watcher::Changes,
+		branch_names: Option<Vec<String>>,
+		branch_info: Option<BranchInfo>,
+	},
+	BannerDismiss,
 
 	// Download events
 	ShowDownloadModal {
@@ -59,24 +107,49 @@ pub enum Events {
 		count: usize,
 		total_file_count: usize,
 	},
+	DownloadStrategy(String),
 	DownloadProgess {
+		count: usize,
 		downloaded_chunk: usize,
 	},
 	DownloadSpeedMeter {
 		bytes_per_s: f64,
 	},
 	DownloadStop,
+	/// Download task ended and released app state
+	DownloadFinished,
 	DownloadCancel,
+	DownloadLimit,
 	DeleteMods,
 
 	// Menu events
 	MenuSettings,
+	PreferencesBrowseCache,
+	PreferencesSave,
+	PreferencesCancel,
+	MenuClearCache,
+	MenuScanMods,
+	MenuUpdateSignatures,
 	MenuAbout,
 	MenuHelp,
 	MenuProfile(String),
 	MenuNewProfile,
 	MenuSaveProfile(String),
 	MenuDeleteProfile,
+	MenuLogout,
+	MenuNetwork,
+	MenuRequireSignature,
+	MenuForgetKey,
+	MenuWatch,
+	MenuMirrors,
+	MenuKeepRules,
+	KeepRuleAdd,
+	KeepRuleRemove,
+	KeepRulesClose,
+	NetworkBrowseCa,
+	NetworkSave,
+	NetworkCancel,
+	MenuInstallMode(InstallMode),
 }
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
@@ -84,9 +157,133 @@ const REPOSITORY: &'static str = env!("CARGO_PKG_REPOSITORY");
 
 const DEFAULT_PROFILE_NAME: &'static str = "default";
 
-// TODO: make this setting
-lazy_static! {
-	static ref LABEL_ALIGN: enums::Align = enums::Align::Left | enums::Align::Inside;
+/// Menu paths are built from translated labels, find_item has to use the same
+fn file_menu(item: &str) -> String {
+	format!("{}/{}", tr!("menu.file"), item)
+}
+
+fn profiles_menu(item: &str) -> String {
+	file_menu(&format!("{}/{}", tr!("menu.profiles"), item))
+}
+
+/// Plan and run a download with the current lists
+/// Holds app state's read lock until it's done
+async fn download(
+	fltk_tx: app::Sender<Events>,
+	app_state: Arc<RwLock<AppState>>,
+	progress_stop_rx: Arc<Mutex<tokio::sync::mpsc::Receiver<bool>>>,
+) {
+	// INFO: this wont drop until download is complete
+	// no need to drop() it manually, write wont be used until download completed
+	let app_state_locked = app_state.read().await;
+
+	let plan = match preview::build(&app_state_locked).await {
+		Ok(plan) => plan,
+		Err(err) => {
+			fltk_tx.send(Events::Alert(err));
+			return;
+		}
+	};
+
+	fltk_tx.send(Events::DeleteMods);
+
+	let strategy_text = if api::is_using_mirror() {
+		tr!(
+			"download.mirror",
+			plan = plan.plan,
+			address = api::active_address().unwrap_or_default()
+		)
+	} else {
+		plan.plan.to_string()
+	};
+
+	match plan.plan.strategy {
+		// INFO: zip download fetches the mods it has no up to date copy of itself
+		planner::DownloadStrategy::Zip | planner::DownloadStrategy::Hybrid => {
+			fltk_tx.send(Events::ShowDownloadModal {
+				total_size: plan.transfer_size,
+			});
+			fltk_tx.send(Events::DownloadStrategy(strategy_text));
+
+			syncer::download_zip(
+				fltk_tx,
+				progress_stop_rx,
+				app_state.clone(),
+				plan.supports_ranges,
+			)
+			.await;
+		}
+		planner::DownloadStrategy::Files => {
+			fltk_tx.send(Events::ShowDownloadModal {
+				total_size: plan.install_size(),
+			});
+			fltk_tx.send(Events::DownloadStrategy(strategy_text));
+
+			syncer::download_files(
+				fltk_tx,
+				progress_stop_rx,
+				app_state.clone(),
+				plan.install_count(),
+			)
+			.await;
+		}
+	}
+}
+
+/// Apply (name, checked) changes of the download list's optional mods
+/// and remember them in current profile's current branch (saved right away)
+async fn apply_optional_changes(
+	profiles_map: &profiles::ProfilesMap,
+	app_state: &mut AppState,
+	changes: Vec<(String, bool)>,
+) {
+	if changes.is_empty() {
+		return;
+	}
+
+	let profile = app_state
+		.profile_name
+		.as_ref()
+		.and_then(|v| profiles_map.get_profile(v));
+
+	if let (Some(profile), Some(branch_name)) = (profile, app_state.branch_name.as_ref()) {
+		for (name, checked) in changes.iter() {
+			profile.select_optional(branch_name, name, *checked);
+		}
+		// INFO: save_profiles goes through every profile, can't hold this one
+		drop(profile);
+		profiles::save_profiles(profiles_map).await;
+	}
+
+	for (name, checked) in changes {
+		app_state.to_download_names.insert(name, checked);
+	}
+}
+
+/// Fill keep rules dialog's list, "@." stops format parsing of patterns
+fn show_keep_rules(browser: &mut browser::HoldBrowser, rules: &[keep::KeepRule]) {
+	browser.clear();
+	for rule in rules {
+		browser.add(&format!("@.{}", rule));
+	}
+}
+
+/// Align main window's labels, rows are (row flex, label)
+fn apply_label_align(align: enums::Align, rows: &mut [(group::Flex, frame::Frame)]) {
+	let mut width = rows
+		.iter()
+		.map(|v| v.1.measure_label().0)
+		.max()
+		.unwrap_or_default();
+	if align.contains(enums::Align::Right) {
+		width += 15;
+	}
+
+	for (row, label) in rows.iter_mut() {
+		label.set_align(align);
+		row.fixed(label, width);
+		row.layout();
+	}
 }
 
 // TODO:
@@ -99,9 +296,22 @@ lazy_static! {
 
 #[tokio::main]
 async fn main() {
+	// INFO: set before parsing arguments (their errors are translated) and before any label is built,
+	// changing it later relabels the built widgets (see PreferencesSave)
+	i18n::set_language(&settings::get().language);
+
+	let args = cli::parse_args();
+	if args.limit_rate.is_some() {
+		throttle::set_limit(args.limit_rate);
+	}
+	// INFO: taken when the first branch is listed
+	let mut cli_preset = args.preset.clone();
+
 	let logo = image::PngImage::from_data(include_bytes!("../syncer_logo.png")).unwrap();
 
 	let app_state = Arc::new(RwLock::new(AppState::default()));
+	// INFO: outside of app state, a running download holds its read lock
+	let is_downloading = Arc::new(AtomicBool::new(false));
 
 	let mut profiles_map = profiles::load_profiles().await;
 
@@ -111,9 +321,16 @@ async fn main() {
 		profiles::save_profiles(&profiles_map).await;
 	}
 
+	let settings = settings::get();
+
+	if args.dry_run {
+		std::process::exit(
+			preview::dry_run(&profiles_map, args.preset.as_deref(), args.json).await,
+		);
+	}
+
 	let app = app::App::default();
-	let widget_theme = fltk_theme::WidgetTheme::new(fltk_theme::ThemeType::Classic);
-	widget_theme.apply();
+	theme::apply(theme::find(&settings.theme));
 	let widget_scheme = fltk_theme::WidgetScheme::new(fltk_theme::SchemeType::Fleet1);
 	widget_scheme.apply();
 
@@ -122,20 +339,26 @@ async fn main() {
 	let progress_stop_rx = Arc::new(Mutex::new(progress_stop_rx));
 
 	// Check if new version is avaliable
-	if let Ok(repo_version) = api::get_repo_version().await {
+	if settings.check_updates
+		&& let Ok(repo_version) = api::get_repo_version().await
+	{
 		if Version::parse(VERSION).is_ok_and(|v| repo_version > v) {
-			fltk_tx.send(Events::Alert(String::from(&format!(
-				"Update avaliable. New version: {}!",
-				repo_version
-			))));
+			fltk_tx.send(Events::Alert(tr!(
+				"alert.update_available",
+				version = repo_version
+			)));
 		}
 	}
 
+	if let Some(reason) = cache::disabled_reason().await {
+		fltk_tx.send(Events::Alert(tr!("alert.cache_disabled", error = reason)));
+	}
+
 	// ----- Main window section  -----
 
 	let mut main_wind = window::Window::default()
 		.with_size(1000, 700)
-		.with_label("Minecraft mod syncer");
+		.with_label(&tr!("main.title"));
 	main_wind.make_resizable(true);
 	main_wind.set_icon(Some(logo.clone()));
 
@@ -152,55 +375,129 @@ async fn main() {
 
 	let mut input_flex = group::Flex::default();
 	let server_ip_label = frame::Frame::default()
-		.with_label("Server ip/domain: ")
-		.with_align(*LABEL_ALIGN);
+		.with_label(&tr!("main.server_address"))
+		.with_align(settings.label_align.to_align());
 	let mut server_ip_input = input::Input::default().with_align(enums::Align::Right);
-	let mut ip_ok_button = button::Button::default().with_label("OK");
+	let mut ip_ok_button = button::Button::default().with_label(&tr!("main.ok"));
 	input_flex.end();
 
-	let mut branch_flex = group::Flex::default();
+	let branch_flex = group::Flex::default();
 	let branch_label = frame::Frame::default()
-		.with_label("Branch: ")
-		.with_align(*LABEL_ALIGN);
+		.with_label(&tr!("main.branch"))
+		.with_align(settings.label_align.to_align());
 	let mut branch_chooser = menu::Choice::default();
 	branch_flex.end();
 
 	let mut mod_dir_flex = group::Flex::default();
 	let mod_dir_label = frame::Frame::default()
-		.with_label("Mods directory: ")
-		.with_align(*LABEL_ALIGN);
+		.with_label(&tr!("main.mods_dir"))
+		.with_align(settings.label_align.to_align());
 	let mut mods_path_input = input::FileInput::default();
-	let mut mods_path_button = button::Button::default().with_label("Browse");
+	let mut mods_path_button = button::Button::default().with_label(&tr!("common.browse"));
 	mod_dir_flex.end();
 
+	// INFO: shown when watcher notices changes on the server, click hides it
+	let mut banner = button::Button::default().with_align(settings.label_align.to_align());
+	banner.set_frame(enums::FrameType::FlatBox);
+	banner.set_color(enums::Color::from_rgb(255, 243, 176));
+	banner.set_label_color(enums::Color::Black);
+	banner.clear_visible_focus();
+	banner.set_tooltip(&tr!("main.banner_tooltip"));
+	banner.hide();
+
 	flex.fixed(&frame::Frame::default(), 10);
 
-	let info_flex = group::Flex::default().size_of_parent().row();
-	let mut download_list = browser::CheckBrowser::default()
-		.with_label("To download")
-		.with_align(enums::Align::Top);
-	let mut delete_list = browser::CheckBrowser::default()
-		.with_label("To delete")
+	let mut filter_flex = group::Flex::default().row();
+	let filter_label = frame::Frame::default()
+		.with_label(&tr!("filter.label"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut filter_input = input::Input::default();
+	filter_input.set_tooltip(&tr!("filter.tooltip"));
+	let mut show_required_check =
+		button::CheckButton::default().with_label(&tr!("filter.required"));
+	let mut show_optional_check =
+		button::CheckButton::default().with_label(&tr!("filter.optional"));
+	let mut show_kept_check = button::CheckButton::default().with_label(&tr!("filter.kept"));
+	let mut sort_choice = menu::Choice::default();
+	sort_choice.add_choice(&tr!("filter.sort_choices"));
+	sort_choice.set_value(0);
+	sort_choice.set_tooltip(&tr!("filter.sort_tooltip"));
+	let mut preset_choice = menu::Choice::default();
+	preset_choice.set_tooltip(&tr!("filter.preset_tooltip"));
+	preset_choice.deactivate();
+	let mut select_all_button = button::Button::default().with_label(&tr!("filter.all_optional"));
+	let mut select_none_button = button::Button::default().with_label(&tr!("filter.no_optional"));
+	filter_flex.end();
+
+	for check in [
+		&mut show_required_check,
+		&mut show_optional_check,
+		&mut show_kept_check,
+	] {
+		check.set_checked(true);
+		check.clear_visible_focus();
+		filter_flex.fixed(&*check, check.measure_label().0 + 30);
+	}
+	filter_flex.fixed(&filter_label, filter_label.measure_label().0 + 10);
+	filter_flex.fixed(&sort_choice, 110);
+	filter_flex.fixed(&preset_choice, 120);
+	for button in [&select_all_button, &select_none_button] {
+		filter_flex.fixed(button, button.measure_label().0 + 20);
+	}
+	filter_flex.set_spacing(5);
+
+	let mut info_flex = group::Flex::default().size_of_parent().row();
+	let mut download_list = widgets::ModBrowser::new(&tr!("main.to_download"));
+	let mut delete_list = widgets::ModBrowser::new(&tr!("main.to_delete"));
+
+	let mut details_flex = group::Flex::default()
+		.column()
+		.with_label(&tr!("details.title"))
 		.with_align(enums::Align::Top);
+	let mut details_icon = frame::Frame::default();
+	let mut details_buffer = text::TextBuffer::default();
+	let mut details_display = text::TextDisplay::default();
+	details_display.set_buffer(details_buffer.clone());
+	details_display.wrap_mode(text::WrapMode::AtBounds, 0);
+	let mut homepage_button = button::Button::default()
+		.with_label(&tr!("details.homepage"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	homepage_button.set_frame(enums::FrameType::NoBox);
+	homepage_button.set_label_color(enums::Color::Blue);
+	homepage_button.set_label_font(enums::Font::HelveticaItalic);
+	homepage_button.clear_visible_focus();
+	// INFO: url is kept in the tooltip, labels would eat '&'
+	homepage_button.set_callback(|b| {
+		if let Some(url) = b.tooltip() {
+			let _ = fltk::utils::open_uri(&url);
+		}
+	});
+	details_flex.fixed(&details_icon, 64);
+	details_flex.fixed(&homepage_button, 25);
+	details_icon.hide();
+	homepage_button.hide();
+	details_flex.end();
+
 	info_flex.end();
 
-	let mut download_but = button::Button::default().with_label("Download");
+	let mut download_flex = group::Flex::default().row();
+	let mut preview_but = button::Button::default().with_label(&tr!("main.preview"));
+	preview_but.set_tooltip(&tr!("main.preview_tooltip"));
+	let mut download_but = button::Button::default().with_label(&tr!("main.download"));
 	//download_but.set_label_font(enums::Font::HelveticaBold);
 	download_but.set_label_size(18);
+	download_flex.fixed(&preview_but, preview_but.measure_label().0 + 30);
+	download_flex.set_spacing(10);
+	download_flex.end();
 
 	main_wind.end();
 
-	if LABEL_ALIGN.contains(enums::Align::Right) {
-		let width = server_ip_label.measure_label().0 + 15;
-		input_flex.fixed(&server_ip_label, width);
-		branch_flex.fixed(&branch_label, width);
-		mod_dir_flex.fixed(&mod_dir_label, width);
-	} else {
-		let width = server_ip_label.measure_label().0;
-		input_flex.fixed(&server_ip_label, width);
-		branch_flex.fixed(&branch_label, width);
-		mod_dir_flex.fixed(&mod_dir_label, width);
-	}
+	let mut label_rows = [
+		(input_flex.clone(), server_ip_label.clone()),
+		(branch_flex.clone(), branch_label.clone()),
+		(mod_dir_flex.clone(), mod_dir_label.clone()),
+	];
+	apply_label_align(settings.label_align.to_align(), &mut label_rows);
 
 	input_flex.fixed(&ip_ok_button, 60);
 	mod_dir_flex.fixed(&mods_path_button, 60);
@@ -209,33 +506,45 @@ async fn main() {
 	flex.fixed(&input_flex, 30);
 	flex.fixed(&branch_flex, 30);
 	flex.fixed(&mod_dir_flex, 35);
-	flex.fixed(&download_but, 40);
+	flex.fixed(&banner, 30);
+	flex.fixed(&filter_flex, 30);
+	flex.fixed(&download_flex, 40);
 
 	flex.set_margin(30);
 	flex.end();
 
-	download_list.clear_visible_focus();
-	delete_list.clear_visible_focus();
+	info_flex.fixed(&details_flex, 260);
+	info_flex.set_spacing(10);
 
 	server_ip_input.emit(fltk_tx, Events::GetBranches);
 	ip_ok_button.emit(fltk_tx, Events::GetBranches);
 	branch_chooser.emit(fltk_tx, Events::GetMods);
 	download_but.emit(fltk_tx, Events::Download);
+	preview_but.emit(fltk_tx, Events::Preview);
+	banner.emit(fltk_tx, Events::BannerDismiss);
 	mods_path_button.emit(fltk_tx, Events::PathBrowse);
 	mods_path_input.emit(fltk_tx, Events::PathSet);
 	download_list.emit(fltk_tx, Events::DownloadListUpdate);
+	filter_input.emit(fltk_tx, Events::FilterChanged);
+	show_required_check.emit(fltk_tx, Events::FilterChanged);
+	show_optional_check.emit(fltk_tx, Events::FilterChanged);
+	show_kept_check.emit(fltk_tx, Events::FilterChanged);
+	sort_choice.emit(fltk_tx, Events::SortChanged);
+	preset_choice.emit(fltk_tx, Events::PresetChosen);
+	select_all_button.emit(fltk_tx, Events::SelectOptionals(true));
+	select_none_button.emit(fltk_tx, Events::SelectOptionals(false));
+	filter_input.set_trigger(enums::CallbackTrigger::Changed);
 	delete_list.emit(fltk_tx, Events::DeleteListUpdate);
 
 	server_ip_input.set_trigger(enums::CallbackTrigger::EnterKeyAlways);
 	mods_path_input.set_trigger(enums::CallbackTrigger::EnterKeyAlways);
-	download_list.set_trigger(enums::CallbackTrigger::Changed);
-	delete_list.set_trigger(enums::CallbackTrigger::Changed);
 
-	// TODO: setting for auto save on exit
 	// TODO: dont use sleep
 	// INFO: save current profile before quiting
 	main_wind.set_callback(move |_| {
-		fltk_tx.send(Events::MenuSaveProfile(String::from("")));
+		if settings::get().auto_save_on_exit {
+			fltk_tx.send(Events::MenuSaveProfile(String::from("")));
+		}
 		tokio::spawn(async {
 			tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 			app::awake_callback(|| app::quit());
@@ -244,21 +553,51 @@ async fn main() {
 	main_wind.set_trigger(enums::CallbackTrigger::Closed);
 
 	menubar.add_emit(
-		"&File/Preferences",
+		&file_menu(&tr!("menu.preferences")),
 		enums::Shortcut::None,
 		menu::MenuFlag::Normal,
 		fltk_tx,
 		Events::MenuSettings,
 	);
+	for mode in InstallMode::ALL {
+		menubar.add_emit(
+			&file_menu(&format!("{}/{}", tr!("menu.install_mode"), mode.label())),
+			enums::Shortcut::None,
+			menu::MenuFlag::Radio,
+			fltk_tx,
+			Events::MenuInstallMode(mode),
+		);
+	}
+	menubar.add_emit(
+		&file_menu(&tr!("menu.clear_cache")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuClearCache,
+	);
+	menubar.add_emit(
+		&file_menu(&tr!("menu.scan_mods")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuScanMods,
+	);
+	menubar.add_emit(
+		&file_menu(&tr!("menu.update_signatures")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuUpdateSignatures,
+	);
 	menubar.add_emit(
-		"&Help/About",
+		&format!("{}/{}", tr!("menu.help"), tr!("menu.about")),
 		enums::Shortcut::None,
 		menu::MenuFlag::Normal,
 		fltk_tx,
 		Events::MenuAbout,
 	);
 	menubar.add_emit(
-		"&Help/Help",
+		&format!("{}/{}", tr!("menu.help"), tr!("menu.help_item")),
 		enums::Shortcut::None,
 		menu::MenuFlag::Normal,
 		fltk_tx,
@@ -274,7 +613,7 @@ async fn main() {
 			}
 
 			menubar.add_emit(
-				&format!("&File/Profiles/{}", &profile_name),
+				&profiles_menu(&profile_name),
 				enums::Shortcut::None,
 				menu::MenuFlag::Normal,
 				fltk_tx,
@@ -283,33 +622,96 @@ async fn main() {
 		}
 	}
 	menubar.add_emit(
-		&format!("&File/Profiles/{}", DEFAULT_PROFILE_NAME),
+		&profiles_menu(DEFAULT_PROFILE_NAME),
 		enums::Shortcut::None,
 		menu::MenuFlag::MenuDivider,
 		fltk_tx,
 		Events::MenuProfile(String::from(DEFAULT_PROFILE_NAME)),
 	);
 	menubar.add_emit(
-		"&File/Profiles/New",
+		&profiles_menu(&tr!("menu.new_profile")),
 		enums::Shortcut::None,
 		menu::MenuFlag::Normal,
 		fltk_tx,
 		Events::MenuNewProfile,
 	);
 	menubar.add_emit(
-		"&File/Profiles/Save",
+		&profiles_menu(&tr!("menu.save_profile")),
 		enums::Shortcut::None,
 		menu::MenuFlag::Normal,
 		fltk_tx,
 		Events::MenuSaveProfile(String::from("")),
 	);
 	menubar.add_emit(
-		"&File/Profiles/Delete",
+		&profiles_menu(&tr!("menu.delete_profile")),
 		enums::Shortcut::None,
-		menu::MenuFlag::Normal,
+		menu::MenuFlag::MenuDivider,
 		fltk_tx,
 		Events::MenuDeleteProfile,
 	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.login")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::LoginRequired,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.logout")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuLogout,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.mirrors")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuMirrors,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.keep_rules")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuKeepRules,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.network")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuNetwork,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.require_signature")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Toggle,
+		fltk_tx,
+		Events::MenuRequireSignature,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.forget_key")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Normal,
+		fltk_tx,
+		Events::MenuForgetKey,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.watch")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Toggle,
+		fltk_tx,
+		Events::MenuWatch,
+	);
+	menubar.add_emit(
+		&profiles_menu(&tr!("menu.auto_sync")),
+		enums::Shortcut::None,
+		menu::MenuFlag::Toggle,
+		fltk_tx,
+		Events::MenuWatch,
+	);
 
 	fltk_tx.send(Events::MenuProfile(String::from(
 		profiles_map.get_last_profile_name(),
@@ -317,11 +719,13 @@ async fn main() {
 
 	main_wind.show();
 
+	tokio::spawn(watcher::watch(fltk_tx, app_state.clone()));
+
 	// ----- Download dialog section  -----
 
 	let mut download_wind = window::Window::default()
-		.with_size(400, 250)
-		.with_label("Downloading ...");
+		.with_size(400, 330)
+		.with_label(&tr!("download.title"));
 	let mut download_flex = group::Flex::default()
 		.size_of_parent()
 		.with_type(group::FlexType::Column);
@@ -339,7 +743,19 @@ async fn main() {
 	let mut current_progress = misc::Progress::default();
 	let mut total_progress = misc::Progress::default();
 
-	let mut cancel_button = button::Button::default().with_label("Cancel");
+	let mut strategy_label = frame::Frame::default()
+		.with_align(enums::Align::Left | enums::Align::Inside | enums::Align::Wrap);
+	strategy_label.set_label_size(11);
+
+	let mut limit_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let limit_label = frame::Frame::default()
+		.with_label(&tr!("download.speed_limit"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut limit_input = input::IntInput::default();
+	limit_flex.fixed(&limit_label, limit_label.measure_label().0 + 10);
+	limit_flex.end();
+
+	let mut cancel_button = button::Button::default().with_label(&tr!("common.cancel"));
 
 	current_progress.set_selection_color(enums::Color::Green);
 	total_progress.set_selection_color(enums::Color::Green);
@@ -350,12 +766,16 @@ async fn main() {
 	download_flex.fixed(&progress_flex, 30);
 	download_flex.fixed(&current_progress, 30);
 	download_flex.fixed(&total_progress, 30);
+	download_flex.fixed(&strategy_label, 40);
+	download_flex.fixed(&limit_flex, 30);
 	download_flex.fixed(&cancel_button, 30);
 
 	download_wind.set_trigger(enums::CallbackTrigger::Closed);
 
 	download_wind.emit(fltk_tx, Events::DownloadCancel);
 	cancel_button.emit(fltk_tx, Events::DownloadCancel);
+	limit_input.emit(fltk_tx, Events::DownloadLimit);
+	limit_input.set_trigger(enums::CallbackTrigger::EnterKeyAlways);
 
 	download_flex.end();
 	download_wind.make_modal(true);
@@ -365,7 +785,7 @@ async fn main() {
 
 	let mut about_win = window::Window::default()
 		.with_size(500, 100)
-		.with_label("About");
+		.with_label(&tr!("about.title"));
 	about_win.set_icon(Some(logo.clone()));
 
 	let mut about_flex = group::Flex::default()
@@ -373,16 +793,16 @@ async fn main() {
 		.size_of_parent();
 
 	frame::Frame::default()
-		.with_label(&format!("Developer: {}", env!("CARGO_PKG_AUTHORS")))
+		.with_label(&tr!("about.developer", name = env!("CARGO_PKG_AUTHORS")))
 		.with_align(enums::Align::Left | enums::Align::Inside);
 	frame::Frame::default()
-		.with_label(&format!("Version: {}", VERSION))
+		.with_label(&tr!("about.version", version = VERSION))
 		.with_align(enums::Align::Left | enums::Align::Inside);
 
 	let mut link_flex = group::Flex::default().with_type(group::FlexType::Row);
 
 	let link_label = frame::Frame::default()
-		.with_label("Repository:")
+		.with_label(&tr!("about.repository"))
 		.with_align(enums::Align::Left | enums::Align::Inside);
 	let mut link_button = button::Button::default()
 		.with_label(&REPOSITORY)
@@ -405,8 +825,389 @@ async fn main() {
 	about_flex.end();
 	about_win.end();
 
+	// ----- Login dialog section  -----
+
+	let mut login_win = window::Window::default()
+		.with_size(400, 250)
+		.with_label(&tr!("login.title"));
+	login_win.set_icon(Some(logo.clone()));
+
+	let mut login_flex = group::Flex::default()
+		.with_type(group::FlexType::Column)
+		.size_of_parent();
+
+	frame::Frame::default()
+		.with_label(&tr!("login.required"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+
+	let mut login_kind_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let login_kind_label = frame::Frame::default()
+		.with_label(&tr!("login.type"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut login_kind_choice = menu::Choice::default();
+	login_kind_choice.add_choice(&tr!("login.kinds"));
+	login_kind_choice.set_value(0);
+	login_kind_flex.end();
+
+	let mut login_name_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let login_name_label = frame::Frame::default()
+		.with_label(&tr!("login.name"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut login_name_input = input::Input::default();
+	login_name_flex.end();
+
+	let mut login_secret_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let login_secret_label = frame::Frame::default()
+		.with_label(&tr!("login.secret"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut login_secret_input = input::SecretInput::default();
+	login_secret_flex.end();
+
+	let login_button_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let mut login_cancel_button = button::Button::default().with_label(&tr!("common.cancel"));
+	let mut login_ok_button = button::Button::default().with_label(&tr!("login.title"));
+	login_button_flex.end();
+
+	let login_label_width = login_name_label.measure_label().0 + 10;
+	login_kind_flex.fixed(&login_kind_label, login_label_width);
+	login_name_flex.fixed(&login_name_label, login_label_width);
+	login_secret_flex.fixed(&login_secret_label, login_label_width);
+
+	login_ok_button.emit(fltk_tx, Events::LoginSubmit);
+	login_cancel_button.emit(fltk_tx, Events::LoginCancel);
+	login_secret_input.emit(fltk_tx, Events::LoginSubmit);
+	login_secret_input.set_trigger(enums::CallbackTrigger::EnterKeyAlways);
+
+	login_flex.fixed(&login_kind_flex, 30);
+	login_flex.fixed(&login_name_flex, 30);
+	login_flex.fixed(&login_secret_flex, 30);
+	login_flex.fixed(&login_button_flex, 30);
+	login_flex.set_spacing(10);
+	login_flex.set_margin(20);
+	login_flex.end();
+	login_win.make_modal(true);
+	login_win.end();
+
+	// ----- Network settings dialog section  -----
+
+	let mut network_win = window::Window::default()
+		.with_size(550, 320)
+		.with_label(&tr!("network.title"));
+	network_win.set_icon(Some(logo.clone()));
+
+	let mut network_flex = group::Flex::default()
+		.with_type(group::FlexType::Column)
+		.size_of_parent();
+
+	let mut proxy_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let proxy_label = frame::Frame::default()
+		.with_label(&tr!("network.proxy"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut proxy_input = input::Input::default();
+	proxy_flex.end();
+
+	let mut ca_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let ca_label = frame::Frame::default()
+		.with_label(&tr!("network.ca_bundle"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut ca_input = input::FileInput::default();
+	let mut ca_button = button::Button::default().with_label(&tr!("common.browse"));
+	ca_flex.fixed(&ca_button, 60);
+	ca_flex.end();
+
+	let mut pin_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let pin_label = frame::Frame::default()
+		.with_label(&tr!("network.pin"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut pin_input = input::Input::default();
+	pin_flex.end();
+
+	let mut connect_timeout_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let connect_timeout_label = frame::Frame::default()
+		.with_label(&tr!("network.connect_timeout"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut connect_timeout_input = input::IntInput::default();
+	connect_timeout_flex.end();
+
+	let mut read_timeout_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let read_timeout_label = frame::Frame::default()
+		.with_label(&tr!("network.read_timeout"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut read_timeout_input = input::IntInput::default();
+	read_timeout_flex.end();
+
+	let network_button_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let mut network_cancel_button = button::Button::default().with_label(&tr!("common.cancel"));
+	let mut network_save_button = button::Button::default().with_label(&tr!("common.save"));
+	network_button_flex.end();
+
+	let network_label_width = ca_label.measure_label().0 + 10;
+	proxy_flex.fixed(&proxy_label, network_label_width);
+	ca_flex.fixed(&ca_label, network_label_width);
+	pin_flex.fixed(&pin_label, network_label_width);
+	connect_timeout_flex.fixed(&connect_timeout_label, network_label_width);
+	read_timeout_flex.fixed(&read_timeout_label, network_label_width);
+
+	proxy_input.set_tooltip(&tr!("network.proxy_tooltip"));
+	pin_input.set_tooltip(&tr!("network.pin_tooltip"));
+
+	ca_button.emit(fltk_tx, Events::NetworkBrowseCa);
+	network_save_button.emit(fltk_tx, Events::NetworkSave);
+	network_cancel_button.emit(fltk_tx, Events::NetworkCancel);
+
+	network_flex.fixed(&proxy_flex, 30);
+	network_flex.fixed(&ca_flex, 30);
+	network_flex.fixed(&pin_flex, 30);
+	network_flex.fixed(&connect_timeout_flex, 30);
+	network_flex.fixed(&read_timeout_flex, 30);
+	network_flex.fixed(&network_button_flex, 30);
+	network_flex.set_spacing(10);
+	network_flex.set_margin(20);
+	network_flex.end();
+	network_win.make_modal(true);
+	network_win.end();
+
+	// ----- Keep rules dialog section  -----
+
+	let mut keep_win = window::Window::default()
+		.with_size(600, 420)
+		.with_label(&tr!("keep.title"));
+	keep_win.set_icon(Some(logo.clone()));
+
+	let mut keep_flex = group::Flex::default()
+		.with_type(group::FlexType::Column)
+		.size_of_parent();
+
+	let mut keep_browser = browser::HoldBrowser::default();
+
+	let mut keep_pattern_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let keep_pattern_label = frame::Frame::default()
+		.with_label(&tr!("keep.pattern"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut keep_pattern_input = input::Input::default();
+	let mut keep_target_choice = menu::Choice::default();
+	keep_target_choice.add_choice(&tr!("keep.target_choices"));
+	keep_target_choice.set_value(0);
+	keep_pattern_flex.fixed(&keep_target_choice, 110);
+	keep_pattern_flex.end();
+
+	let mut keep_scope_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let keep_scope_label = frame::Frame::default()
+		.with_label(&tr!("keep.scope"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut keep_scope_choice = menu::Choice::default();
+	keep_scope_choice.add_choice(&tr!("keep.scope_choices"));
+	keep_scope_choice.set_value(0);
+	keep_scope_flex.end();
+
+	let mut keep_size_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let keep_size_label = frame::Frame::default()
+		.with_label(&tr!("keep.max_size_mb"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut keep_size_input = input::IntInput::default();
+	keep_size_flex.end();
+
+	let mut keep_age_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let keep_age_label = frame::Frame::default()
+		.with_label(&tr!("keep.max_age_days"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut keep_age_input = input::IntInput::default();
+	keep_age_flex.end();
+
+	let keep_button_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let mut keep_remove_button = button::Button::default().with_label(&tr!("keep.remove"));
+	let mut keep_add_button = button::Button::default().with_label(&tr!("keep.add"));
+	let mut keep_close_button = button::Button::default().with_label(&tr!("common.close"));
+	keep_button_flex.end();
+
+	let keep_label_width = [
+		&keep_pattern_label,
+		&keep_scope_label,
+		&keep_size_label,
+		&keep_age_label,
+	]
+	.iter()
+	.map(|v| v.measure_label().0)
+	.max()
+	.unwrap_or_default()
+		+ 10;
+	keep_pattern_flex.fixed(&keep_pattern_label, keep_label_width);
+	keep_scope_flex.fixed(&keep_scope_label, keep_label_width);
+	keep_size_flex.fixed(&keep_size_label, keep_label_width);
+	keep_age_flex.fixed(&keep_age_label, keep_label_width);
+
+	keep_pattern_input.set_tooltip(&tr!("keep.pattern_tooltip"));
+	keep_size_input.set_tooltip(&tr!("keep.empty_tooltip"));
+	keep_age_input.set_tooltip(&tr!("keep.empty_tooltip"));
+
+	keep_add_button.emit(fltk_tx, Events::KeepRuleAdd);
+	keep_remove_button.emit(fltk_tx, Events::KeepRuleRemove);
+	keep_close_button.emit(fltk_tx, Events::KeepRulesClose);
+	keep_win.emit(fltk_tx, Events::KeepRulesClose);
+
+	keep_flex.fixed(&keep_pattern_flex, 30);
+	keep_flex.fixed(&keep_scope_flex, 30);
+	keep_flex.fixed(&keep_size_flex, 30);
+	keep_flex.fixed(&keep_age_flex, 30);
+	keep_flex.fixed(&keep_button_flex, 30);
+	keep_flex.set_spacing(10);
+	keep_flex.set_margin(20);
+	keep_flex.end();
+	keep_win.make_modal(true);
+	keep_win.end();
+
+	// ----- Preferences dialog section  -----
+
+	let mut prefs_win = window::Window::default()
+		.with_size(550, 450)
+		.with_label(&tr!("prefs.title"));
+	prefs_win.set_icon(Some(logo.clone()));
+
+	let mut prefs_flex = group::Flex::default()
+		.with_type(group::FlexType::Column)
+		.size_of_parent();
+
+	let mut align_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let align_label = frame::Frame::default()
+		.with_label(&tr!("prefs.label_align"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut align_choice = menu::Choice::default();
+	align_choice.add_choice(&tr!("prefs.align_choices"));
+	align_flex.end();
+
+	let mut theme_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let theme_label = frame::Frame::default()
+		.with_label(&tr!("prefs.theme"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	// INFO: filled when opened, user themes can change meanwhile
+	let mut theme_choice = menu::Choice::default();
+	theme_flex.end();
+
+	let mut language_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let language_label = frame::Frame::default()
+		.with_label(&tr!("prefs.language"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut language_choice = menu::Choice::default();
+	for (_, name) in settings::LANGUAGES {
+		language_choice.add_choice(name);
+	}
+	language_flex.end();
+
+	let auto_save_check = button::CheckButton::default().with_label(&tr!("prefs.auto_save"));
+	let check_updates_check =
+		button::CheckButton::default().with_label(&tr!("prefs.check_updates"));
+
+	let mut concurrency_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let concurrency_label = frame::Frame::default()
+		.with_label(&tr!("prefs.concurrency"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut concurrency_input = input::IntInput::default();
+	concurrency_flex.end();
+
+	let mut cache_dir_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let cache_dir_label = frame::Frame::default()
+		.with_label(&tr!("prefs.cache_dir"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut cache_dir_input = input::FileInput::default();
+	let mut cache_dir_button = button::Button::default().with_label(&tr!("common.browse"));
+	cache_dir_flex.fixed(&cache_dir_button, 60);
+	cache_dir_flex.end();
+
+	let mut cache_size_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let cache_size_label = frame::Frame::default()
+		.with_label(&tr!("prefs.cache_size"))
+		.with_align(enums::Align::Left | enums::Align::Inside);
+	let mut cache_size_input = input::IntInput::default();
+	cache_size_flex.end();
+
+	let mut cache_usage_label =
+		frame::Frame::default().with_align(enums::Align::Left | enums::Align::Inside);
+
+	let prefs_button_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let mut prefs_cancel_button = button::Button::default().with_label(&tr!("common.cancel"));
+	let mut prefs_save_button = button::Button::default().with_label(&tr!("common.save"));
+	prefs_button_flex.end();
+
+	let prefs_label_width = cache_size_label.measure_label().0 + 10;
+	align_flex.fixed(&align_label, prefs_label_width);
+	theme_flex.fixed(&theme_label, prefs_label_width);
+	language_flex.fixed(&language_label, prefs_label_width);
+	concurrency_flex.fixed(&concurrency_label, prefs_label_width);
+	cache_dir_flex.fixed(&cache_dir_label, prefs_label_width);
+	cache_size_flex.fixed(&cache_size_label, prefs_label_width);
+
+	cache_dir_input.set_tooltip(&tr!("prefs.cache_dir_tooltip"));
+
+	cache_dir_button.emit(fltk_tx, Events::PreferencesBrowseCache);
+	prefs_save_button.emit(fltk_tx, Events::PreferencesSave);
+	prefs_cancel_button.emit(fltk_tx, Events::PreferencesCancel);
+
+	prefs_flex.fixed(&align_flex, 30);
+	prefs_flex.fixed(&theme_flex, 30);
+	prefs_flex.fixed(&language_flex, 30);
+	prefs_flex.fixed(&auto_save_check, 30);
+	prefs_flex.fixed(&check_updates_check, 30);
+	prefs_flex.fixed(&concurrency_flex, 30);
+	prefs_flex.fixed(&cache_dir_flex, 30);
+	prefs_flex.fixed(&cache_size_flex, 30);
+	prefs_flex.fixed(&cache_usage_label, 20);
+	prefs_flex.fixed(&prefs_button_flex, 30);
+	prefs_flex.set_spacing(10);
+	prefs_flex.set_margin(20);
+	prefs_flex.end();
+	prefs_win.make_modal(true);
+	prefs_win.end();
+
+	// ----- Preview dialog section  -----
+
+	let mut preview_win = window::Window::default()
+		.with_size(600, 500)
+		.with_label(&tr!("preview.title"));
+	preview_win.set_icon(Some(logo.clone()));
+
+	let mut preview_flex = group::Flex::default()
+		.with_type(group::FlexType::Column)
+		.size_of_parent();
+
+	let mut preview_buffer = text::TextBuffer::default();
+	let mut preview_display = text::TextDisplay::default();
+	preview_display.set_buffer(preview_buffer.clone());
+
+	let preview_button_flex = group::Flex::default().with_type(group::FlexType::Row);
+	let mut preview_json_button = button::Button::default().with_label(&tr!("preview.export_json"));
+	let mut preview_markdown_button =
+		button::Button::default().with_label(&tr!("preview.export_markdown"));
+	let mut preview_cancel_button = button::Button::default().with_label(&tr!("common.cancel"));
+	let mut preview_download_button = button::Button::default().with_label(&tr!("main.download"));
+	preview_button_flex.end();
+
+	preview_json_button.emit(fltk_tx, Events::PreviewExport(preview::ExportFormat::Json));
+	preview_markdown_button.emit(
+		fltk_tx,
+		Events::PreviewExport(preview::ExportFormat::Markdown),
+	);
+	preview_cancel_button.emit(fltk_tx, Events::PreviewCancel);
+	preview_download_button.emit(fltk_tx, Events::PreviewConfirm);
+	preview_win.emit(fltk_tx, Events::PreviewCancel);
+
+	preview_flex.fixed(&preview_button_flex, 30);
+	preview_flex.set_spacing(10);
+	preview_flex.set_margin(20);
+	preview_flex.end();
+	preview_win.make_modal(true);
+	preview_win.end();
+
 	// ----- Event handling section  -----
 
+	let mut current_file_count = 0;
+	let mut theme_names = Vec::new();
+	// INFO: plan shown in the preview dialog, kept for exporting
+	let mut preview_plan: Option<preview::SyncPlan> = None;
+	// INFO: changes which came in while downloading, applied when it's finished
+	let mut pending_mods: Option<BranchInfo> = None;
+	let mut pending_watch: Option<(watcher::Changes, Option<Vec<String>>, Option<BranchInfo>)> =
+		None;
+
 	while app.wait() {
 		if let Some(val) = fltk_rx.recv() {
 			match val {
@@ -428,6 +1229,12 @@ async fn main() {
 
 					delete_list.clear();
 					download_list.clear();
+					details_buffer.set_text("");
+					details_icon.hide();
+					homepage_button.hide();
+					details_flex.layout();
+					preset_choice.clear();
+					preset_choice.deactivate();
 					branch_chooser.clear();
 
 					app_state_locked.to_delete_names.clear();
@@ -436,11 +1243,20 @@ async fn main() {
 					app_state_locked.branch_name = None;
 					app_state_locked.server_api_address = None;
 					app_state_locked.server_main_address = None;
+					app_state_locked.branch_names.clear();
 
 					if address.len() == 0 {
 						continue;
 					}
 
+					let mirrors = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_profile(v))
+						.map(|v| v.mirrors.clone())
+						.unwrap_or_default();
+					api::set_mirrors(&address, &mirrors);
+
 					// TODO: only set these after checking if address works
 					app_state_locked.server_api_address = Some(address.clone() + "/api");
 					app_state_locked.server_main_address = Some(address);
@@ -451,10 +1267,34 @@ async fn main() {
 						let app_state_locked = app_state.read().await;
 						let api_path = app_state_locked.server_api_address.as_ref().unwrap();
 
+						// INFO: unreachable hoster is handled by get_branch_names (offline cache)
+						match api::get_server_info(api_path).await {
+							Ok(server_info) => {
+								println!(
+									"Server API version {} (capabilities: {})",
+									server_info.api_version,
+									server_info.capabilities.join(", ")
+								);
+
+								match server_info.check_compatibility() {
+									Ok(None) => {}
+									Ok(Some(warning)) => fltk_tx.send(Events::Alert(warning)),
+									Err(err) => {
+										fltk_tx.send(Events::BranchError(err));
+										return;
+									}
+								}
+							}
+							Err(err) => println!("Cannot get server version. {}", err),
+						}
+
 						match api::get_branch_names(api_path).await {
 							Ok(branch_names) => {
 								fltk_tx.send(Events::BranchesResult(branch_names));
 							}
+							Err(ManifestError::Request(err)) if api::is_unauthorized(&err) => {
+								fltk_tx.send(Events::LoginRequired);
+							}
 							Err(err) => {
 								println!("Cannot get branch names. {}", err);
 								fltk_tx.send(Events::BranchError(err.to_string()));
@@ -463,13 +1303,14 @@ async fn main() {
 					});
 				}
 				Events::BranchesResult(branch_names) => {
-					let app_state_locked = app_state.read().await;
+					let mut app_state_locked = app_state.write().await;
 
 					println!("Got branches: {:?}", branch_names);
 
-					for branch_name in branch_names {
-						branch_chooser.add_choice(&branch_name);
+					for branch_name in branch_names.iter() {
+						branch_chooser.add_choice(branch_name);
 					}
+					app_state_locked.branch_names = branch_names;
 
 					branch_chooser.set_value(0);
 
@@ -491,7 +1332,7 @@ async fn main() {
 					branch_chooser.clear();
 					branch_chooser.set_damage(true);
 
-					fltk_tx.send(Events::Alert(format!("Failed to get branches. {}", err)));
+					fltk_tx.send(Events::Alert(tr!("alert.branches_failed", error = err)));
 				}
 				Events::GetMods => {
 					let mut app_state_locked = app_state.write().await;
@@ -500,9 +1341,7 @@ async fn main() {
 					let dir = PathBuf::from(&mods_path_str);
 
 					if !syncer::is_mods_folder(&dir) {
-						fltk_tx.send(Events::Alert(String::from(
-							"Selected folder isn't minecraft mods folder!",
-						)));
+						fltk_tx.send(Events::Alert(tr!("alert.not_mods_folder")));
 						app_state_locked.mods_path = None;
 						mods_path_input.set_value("");
 					} else {
@@ -511,6 +1350,12 @@ async fn main() {
 
 					delete_list.clear();
 					download_list.clear();
+					details_buffer.set_text("");
+					details_icon.hide();
+					homepage_button.hide();
+					details_flex.layout();
+					preset_choice.clear();
+					preset_choice.deactivate();
 
 					app_state_locked.to_delete_names.clear();
 					app_state_locked.to_download_names.clear();
@@ -538,6 +1383,12 @@ async fn main() {
 							Ok(mods) => {
 								fltk_tx.send(Events::ModsResult(mods));
 							}
+							Err(ManifestError::Request(err)) if api::is_unauthorized(&err) => {
+								fltk_tx.send(Events::LoginRequired);
+							}
+							Err(ManifestError::UntrustedKey(key)) => {
+								fltk_tx.send(Events::TrustKey(key));
+							}
 							Err(err) => {
 								println!("Cannot get mods. {}", err);
 								fltk_tx.send(Events::ModsError(err.to_string()));
@@ -549,12 +1400,21 @@ async fn main() {
 					// INFO: dont let this event run multiple times at once
 					let app_state_write_access = app_state.try_write();
 					if app_state_write_access.is_err() {
+						if is_downloading.load(Ordering::Relaxed) {
+							pending_mods = Some(branch_info);
+						}
 						continue;
 					}
 					let mut app_state_locked = app_state_write_access.unwrap();
 
 					delete_list.clear();
 					download_list.clear();
+					details_buffer.set_text("");
+					details_icon.hide();
+					homepage_button.hide();
+					details_flex.layout();
+					preset_choice.clear();
+					preset_choice.deactivate();
 
 					app_state_locked.to_delete_names.clear();
 					app_state_locked.to_download_names.clear();
@@ -571,134 +1431,415 @@ async fn main() {
 
 					let (to_deletes, to_delete_optionals) =
 						syncer::get_mods_to_delete(remote_mods, &local_mod_names);
-					let to_downloads = syncer::get_mods_to_download(remote_mods, &local_mod_names);
-
-					let profile = profiles_map
-						.get_profile(app_state_locked.profile_name.as_ref().unwrap())
-						.unwrap();
-					let keep_mods_branch = profile
-						.keep_mods_in_branch
-						.get(app_state_locked.branch_name.as_ref().unwrap());
+					let to_downloads =
+						syncer::get_mods_to_download(remote_mods, &local_mod_names, mods_pathbuf);
+					let mods_path = mods_pathbuf.clone();
+					let remote_optionals: Vec<String> = remote_mods
+						.iter()
+						.filter(|v| v.is_optional)
+						.map(|v| v.name.clone())
+						.collect();
+					let groups = app_state_locked
+						.branch_info
+						.as_ref()
+						.unwrap()
+						.groups
+						.clone();
+					let preset_names: Vec<String> = app_state_locked
+						.branch_info
+						.as_ref()
+						.unwrap()
+						.presets
+						.iter()
+						.map(|v| v.name.clone())
+						.collect();
+
+					let profile_name = app_state_locked.profile_name.clone().unwrap();
+					let branch_name = app_state_locked.branch_name.clone().unwrap();
+
+					let profile = profiles_map.get_profile(&profile_name).unwrap();
+					let keep_rules = profile.keep_rules.clone();
+					let selected_optionals = profile
+						.selected_optionals
+						.get(&branch_name)
+						.map(|v| v.clone())
+						.unwrap_or_default();
+					let known_optionals =
+						profile.known_optionals.get(&branch_name).map(|v| v.clone());
 
 					for to_delete in to_deletes.iter() {
-						let is_checked = keep_mods_branch
-							.as_ref()
-							.and_then(|v| Some(!v.contains(to_delete)))
-							.unwrap_or(true);
-
-						delete_list.add(to_delete, is_checked);
+						let is_checked =
+							!keep::is_kept(&keep_rules, &branch_name, &mods_path.join(to_delete));
+
+						let (size, date) = syncer::size_and_date(&mods_path.join(to_delete));
+						delete_list.add(
+							to_delete,
+							is_checked,
+							widgets::EntryKind::Deletable,
+							size,
+							date,
+						);
 						app_state_locked
 							.to_delete_names
 							.insert(to_delete.to_string(), is_checked);
 					}
 
+					if !to_delete_optionals.is_empty() {
+						delete_list.add_header(&tr!("main.installed_optionals"));
+					}
 					for to_delete_optional in to_delete_optionals.iter() {
 						let is_checked = false;
 
-						delete_list.add(to_delete_optional, is_checked);
+						let (size, date) =
+							syncer::size_and_date(&mods_path.join(to_delete_optional));
+						delete_list.add(
+							to_delete_optional,
+							is_checked,
+							widgets::EntryKind::OptionalInstalled,
+							size,
+							date,
+						);
 						app_state_locked
 							.to_delete_names
 							.insert(to_delete_optional.to_string(), is_checked);
 					}
 
-					for to_download in to_downloads.iter() {
-						let is_checked = !to_download.is_optional;
-
-						download_list.add(&to_download.name, is_checked);
+					// INFO: required ones first, optionals get their own group
+					let (optional_downloads, required_downloads): (Vec<_>, Vec<_>) =
+						to_downloads.iter().partition(|v| v.is_optional);
+
+					for to_download in required_downloads.iter() {
+						download_list.add(
+							&to_download.name,
+							true,
+							widgets::EntryKind::Required,
+							to_download.size,
+							to_download.mod_date,
+						);
 						app_state_locked
 							.to_download_names
-							.insert(to_download.name.clone(), is_checked);
+							.insert(to_download.name.clone(), true);
 					}
 
-					delete_list.set_damage(true);
-					download_list.set_damage(true);
-				}
-				Events::ModsError(err) => {
-					fltk_tx.send(Events::Alert(format!("Failed to get mods. {}", err)));
-				}
-				Events::Download => {
-					let app_state = app_state.clone();
-					let progress_stop_rx = progress_stop_rx.clone();
-
-					tokio::spawn(async move {
-						// INFO: this wont drop until download is complete
-						// no need to drop() it manually, write wont be used until download completed
-						let app_state_locked = app_state.read().await;
+					// INFO: first visit of a branch only learns its optionals,
+					// ones added later are flagged and asked about once
+					let new_optionals: Vec<String> = match known_optionals.as_ref() {
+						Some(known) => optional_downloads
+							.iter()
+							.filter(|v| !known.contains(&v.name))
+							.map(|v| v.name.clone())
+							.collect(),
+						None => Vec::new(),
+					};
 
-						let branch_info = match app_state_locked.branch_info.as_ref() {
-							Some(branch_info) => branch_info,
-							None => {
-								fltk_tx.send(Events::Alert(String::from(
-									"Please set server address (e.g. themoonbase.dnet.hu/minecraft)",
-								)));
-								return;
-							}
-						};
-						let zip_file = &branch_info.zip;
-						let mcmods = &branch_info.mods;
-
-						if app_state_locked.mods_path.is_none() {
-							fltk_tx.send(Events::Alert(format!(
-								"Please set 'mods' folder path (e.g. {})!",
-								syncer::get_os_default_mods_folder()
-									.as_ref()
-									.and_then(|e| e.to_str())
-									.unwrap_or("whats your platform?")
-							)));
-							return;
+					// INFO: hoster's groups in its order, ungrouped optionals after them
+					let mut grouped = vec![Vec::new(); groups.len()];
+					let mut ungrouped = Vec::new();
+					for to_download in optional_downloads {
+						match groups
+							.iter()
+							.position(|v| v.mods.contains(&to_download.name))
+						{
+							Some(i) => grouped[i].push(to_download),
+							None => ungrouped.push(to_download),
 						}
+					}
 
-						let to_downloads: HashSet<&String> = app_state_locked
-							.to_download_names
-							.iter()
-							.filter_map(|e| e.1.then_some(e.0))
-							.collect();
+					let mut sections: Vec<(String, api::GroupKind, Vec<_>)> = groups
+						.iter()
+						.zip(grouped)
+						.filter(|(_, mods)| !mods.is_empty())
+						.map(|(group, mods)| {
+							let title = match group.kind {
+								api::GroupKind::Any => group.name.clone(),
+								api::GroupKind::OneOf => {
+									tr!("main.group_one_of", name = group.name)
+								}
+								api::GroupKind::AllOrNothing => {
+									tr!("main.group_all_or_nothing", name = group.name)
+								}
+							};
+							(title, group.kind, mods)
+						})
+						.collect();
+					if !ungrouped.is_empty() {
+						sections.push((tr!("main.optional_mods"), api::GroupKind::Any, ungrouped));
+					}
 
-						fltk_tx.send(Events::DeleteMods);
+					for (title, kind, mods) in sections {
+						download_list.add_group(&title, kind);
 
-						let mcmods: Vec<&api::Mod> = mcmods
-							.into_iter()
-							.filter(|x| to_downloads.contains(&x.name))
-							.collect();
+						for to_download in mods {
+							let is_checked = selected_optionals.contains(&to_download.name);
 
-						let total_size = mcmods.iter().fold(0, |acc, x| acc + x.size);
-						let total_count = mcmods.len();
+							download_list.add(
+								&to_download.name,
+								is_checked,
+								widgets::EntryKind::Optional,
+								to_download.size,
+								to_download.mod_date,
+							);
+							app_state_locked
+								.to_download_names
+								.insert(to_download.name.clone(), is_checked);
+						}
+					}
+					for name in new_optionals.iter() {
+						download_list.mark_new(name);
+					}
 
-						// INFO: if zip is not present, download all files separately
-						let zip_size = zip_file
-							.is_present
-							.then_some(zip_file.size)
-							.unwrap_or_else(u64::max_value);
+					drop(profile);
+					if known_optionals.as_ref() != Some(&remote_optionals)
+						&& let Some(profile) = profiles_map.get_mut_profile(&profile_name)
+					{
+						profile
+							.known_optionals
+							.insert(branch_name, remote_optionals);
+						drop(profile);
+						profiles::save_profiles(&profiles_map).await;
+					}
+					if !new_optionals.is_empty() {
+						fltk_tx.send(Events::AskNewOptionals(new_optionals));
+					}
 
-						// INFO: download zip even if it's bigger by 5% than files
-						// TODO: generalize more
-						if total_size > zip_size * 95 / 100 {
-							fltk_tx.send(Events::ShowDownloadModal {
-								total_size: zip_size,
-							});
+					// INFO: remembered choices may break groups the hoster added or changed since
+					let fixed = download_list.enforce_rules();
+					apply_optional_changes(&profiles_map, &mut app_state_locked, fixed).await;
 
-							syncer::download_zip(fltk_tx, progress_stop_rx, app_state.clone())
-								.await;
-						} else {
-							fltk_tx.send(Events::ShowDownloadModal {
-								total_size: total_size,
-							});
-
-							syncer::download_files(
-								fltk_tx,
-								progress_stop_rx,
-								app_state.clone(),
-								total_count,
-							)
-							.await;
+					preset_choice.clear();
+					if !preset_names.is_empty() {
+						preset_choice.add_choice(&tr!("filter.preset"));
+						for name in preset_names.iter() {
+							preset_choice.add_choice(&name.replace('/', "\\/").replace('|', " "));
 						}
-					});
-				}
+						preset_choice.set_value(0);
+						preset_choice.activate();
+					} else {
+						preset_choice.deactivate();
+					}
+					if let Some(name) = cli_preset.take() {
+						fltk_tx.send(Events::ApplyPreset(name));
+					}
+
+					delete_list.rebuild();
+					download_list.rebuild();
+
+					if api::is_offline() {
+						banner.set_label(&tr!("banner.offline"));
+						banner.show();
+						flex.layout();
+					} else if api::is_using_mirror() {
+						banner.set_label(&tr!(
+							"banner.mirror",
+							address = api::active_address().unwrap_or_default()
+						));
+						banner.show();
+						flex.layout();
+					}
+				}
+				Events::ModsError(err) => {
+					fltk_tx.send(Events::Alert(tr!("alert.mods_failed", error = err)));
+				}
+				Events::LoginRequired => {
+					let app_state_locked = app_state.read().await;
+
+					// INFO: prefill with profile's previous login
+					if let Some(kind) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_profile(v))
+						.and_then(|v| v.auth.clone())
+					{
+						match kind {
+							AuthKind::Basic { username } => {
+								login_kind_choice.set_value(0);
+								login_name_input.set_value(&username);
+							}
+							AuthKind::Bearer => {
+								login_kind_choice.set_value(1);
+								login_name_input.set_value("");
+							}
+							AuthKind::Header { name } => {
+								login_kind_choice.set_value(2);
+								login_name_input.set_value(&name);
+							}
+						}
+					}
+
+					login_secret_input.set_value("");
+					login_win.show();
+				}
+				Events::TrustKey(key) => {
+					let choice = dialog::choice2_default(
+						&tr!("dialog.trust_key", fingerprint = signing::fingerprint(&key)),
+						&tr!("common.cancel"),
+						&tr!("dialog.trust"),
+						"",
+					);
+
+					let app_state_locked = app_state.read().await;
+
+					if choice != Some(1) {
+						let is_required = app_state_locked
+							.profile_name
+							.as_ref()
+							.and_then(|v| profiles_map.get_profile(v))
+							.is_some_and(|v| v.require_signature);
+
+						if is_required {
+							fltk_tx.send(Events::Alert(tr!("alert.key_not_trusted")));
+						} else {
+							// INFO: not asked again this session, mods are listed unverified like before
+							api::decline_key(&key);
+							fltk_tx.send(Events::GetMods);
+						}
+						continue;
+					}
+
+					if let Some(mut profile) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						profile.trusted_key = Some(key);
+						api::set_signature_policy(SignaturePolicy {
+							required: profile.require_signature,
+							trusted_key: profile.trusted_key.clone(),
+						});
+					}
+
+					profiles::save_profiles(&profiles_map).await;
+
+					fltk_tx.send(Events::GetMods);
+				}
+				Events::LoginSubmit => {
+					let name = login_name_input.value().trim().to_string();
+					let secret = login_secret_input.value();
+
+					let kind = match login_kind_choice.value() {
+						0 => AuthKind::Basic { username: name },
+						1 => AuthKind::Bearer,
+						_ => AuthKind::Header { name },
+					};
+
+					if let AuthKind::Header { name } = &kind
+						&& name.is_empty()
+					{
+						fltk_tx.send(Events::Alert(tr!("alert.header_empty")));
+						continue;
+					}
+
+					login_win.hide();
+
+					let mut app_state_locked = app_state.write().await;
+
+					if let Some(profile_name) = app_state_locked.profile_name.as_ref()
+						&& let Some(mut profile) = profiles_map.get_mut_profile(profile_name)
+					{
+						if let Err(err) = auth::set_secret(profile_name, &secret) {
+							fltk_tx
+								.send(Events::Alert(tr!("alert.login_store_failed", error = err)));
+						}
+
+						profile.auth = Some(kind.clone());
+					}
+
+					profiles::save_profiles(&profiles_map).await;
+
+					api::set_credentials(Some(Credentials { kind, secret }));
+
+					// INFO: force refetching branches with the new credentials
+					app_state_locked.server_main_address = None;
+					fltk_tx.send(Events::GetBranches);
+				}
+				Events::LoginCancel => {
+					login_win.hide();
+				}
+				Events::Download => {
+					if is_downloading.swap(true, Ordering::Relaxed) {
+						fltk_tx.send(Events::Alert(tr!("alert.download_running")));
+						continue;
+					}
+
+					let app_state = app_state.clone();
+					let progress_stop_rx = progress_stop_rx.clone();
+					let is_downloading = is_downloading.clone();
+
+					tokio::spawn(async move {
+						download(fltk_tx, app_state, progress_stop_rx).await;
+
+						is_downloading.store(false, Ordering::Relaxed);
+						fltk_tx.send(Events::DownloadFinished);
+					});
+				}
+				Events::DownloadFinished => {
+					if let Some(branch_info) = pending_mods.take() {
+						fltk_tx.send(Events::ModsResult(branch_info));
+					}
+					if let Some((changes, branch_names, branch_info)) = pending_watch.take() {
+						fltk_tx.send(Events::WatchChanges {
+							changes,
+							branch_names,
+							branch_info,
+						});
+					}
+				}
+				Events::Preview => {
+					let app_state = app_state.clone();
+
+					tokio::spawn(async move {
+						let app_state_locked = app_state.read().await;
+
+						match preview::build(&app_state_locked).await {
+							Ok(plan) => fltk_tx.send(Events::PreviewResult(plan)),
+							Err(err) => fltk_tx.send(Events::Alert(err)),
+						}
+					});
+				}
+				Events::PreviewResult(plan) => {
+					preview_buffer.set_text(&plan.to_markdown());
+					preview_plan = Some(plan);
+					preview_win.show();
+				}
+				Events::PreviewExport(format) => {
+					let plan = match preview_plan.as_ref() {
+						Some(plan) => plan,
+						None => continue,
+					};
+
+					let path = match dialog::file_chooser(
+						&tr!("preview.choose_export"),
+						&format!("*.{}", format.extension()),
+						format!("sync-plan-{}.{}", plan.branch, format.extension()),
+						false,
+					) {
+						Some(path) => path,
+						None => continue,
+					};
+
+					if let Err(err) = tokio::fs::write(&path, format.render(plan)).await {
+						fltk_tx.send(Events::Alert(tr!("alert.export_failed", error = err)));
+						continue;
+					}
+					fltk_tx.send(Events::Message(tr!("message.plan_exported", path = path)));
+				}
+				Events::PreviewConfirm => {
+					preview_win.hide();
+					preview_plan = None;
+
+					// INFO: Download works the plan out again from the same lists,
+					// only the measured connection (and so the strategy) can differ
+					fltk_tx.send(Events::Download);
+				}
+				Events::PreviewCancel => {
+					preview_win.hide();
+					preview_plan = None;
+				}
 				Events::PathSet => {
 					fltk_tx.send(Events::GetMods);
 				}
 				Events::PathBrowse => {
-					if let Some(dir) = dialog::dir_chooser("Choose a directory", "", false) {
+					if let Some(dir) = dialog::dir_chooser(&tr!("dialog.choose_dir"), "", false) {
 						mods_path_input.set_value(&dir);
 						fltk_tx.send(Events::PathSet);
 					}
@@ -712,27 +1853,16 @@ async fn main() {
 					let mut app_state_locked = app_state.write().await;
 
 					let modname = download_list.text(index).unwrap();
-					let is_checked = download_list.checked(index);
-
-					let remote_mods = &app_state_locked.branch_info.as_ref().unwrap().mods;
-
-					// INFO: if item gets unchecked but not supposed to (aka it's required) then
-					// give error and recheck the item
-					if !is_checked
-						&& !remote_mods
-							.iter()
-							.find(|&e| e.name == modname)
-							.is_some_and(|v| v.is_optional)
-					{
-						download_list.set_checked(index);
-						fltk_tx.send(Events::Alert(String::from("Cannot uncheck required mod!")));
-						continue;
-					}
-
-					*app_state_locked
-						.to_download_names
-						.get_mut(&modname)
-						.unwrap() = is_checked;
+					fltk_tx.send(Events::ShowModDetails(modname));
+
+					// INFO: required mods are locked, so only optionals can change,
+					// group rules may have changed others besides the clicked one
+					apply_optional_changes(
+						&profiles_map,
+						&mut app_state_locked,
+						download_list.changed(),
+					)
+					.await;
 				}
 				Events::DeleteListUpdate => {
 					let index = delete_list.value();
@@ -744,6 +1874,7 @@ async fn main() {
 
 					let modname = delete_list.text(index).unwrap();
 					let is_checked = delete_list.checked(index);
+					fltk_tx.send(Events::ShowModDetails(modname.clone()));
 
 					*app_state_locked.to_delete_names.get_mut(&modname).unwrap() = is_checked;
 
@@ -760,71 +1891,283 @@ async fn main() {
 						continue;
 					}
 
-					if let Some(profile) = app_state_locked
-						.profile_name
+					// INFO: only exact name rules change here, patterns are edited in the rules dialog
+					if let (Some(mut profile), Some(branch_name)) = (
+						app_state_locked
+							.profile_name
+							.as_ref()
+							.and_then(|v| profiles_map.get_mut_profile(v)),
+						app_state_locked.branch_name.as_ref(),
+					) {
+						keep::set_kept(&mut profile.keep_rules, &modname, branch_name, !is_checked);
+					}
+				}
+				Events::FilterChanged => {
+					let filter = widgets::Filter {
+						text: filter_input.value(),
+						required: show_required_check.is_checked(),
+						optional: show_optional_check.is_checked(),
+						kept: show_kept_check.is_checked(),
+					};
+
+					download_list.set_filter(filter.clone());
+					delete_list.set_filter(filter);
+				}
+				Events::SortChanged => {
+					let sort_by = match sort_choice.value() {
+						1 => widgets::SortBy::Size,
+						2 => widgets::SortBy::Date,
+						_ => widgets::SortBy::Name,
+					};
+
+					download_list.set_sort(sort_by);
+					delete_list.set_sort(sort_by);
+				}
+				Events::SelectOptionals(checked) => {
+					let mut app_state_locked = app_state.write().await;
+
+					// INFO: only the shown ones, so a filter can narrow what gets selected
+					let changed =
+						download_list.set_all_checked(widgets::EntryKind::Optional, checked);
+					apply_optional_changes(&profiles_map, &mut app_state_locked, changed).await;
+				}
+				Events::AskNewOptionals(names) => {
+					let choice = dialog::choice2_default(
+						&tr!(
+							"dialog.new_optionals",
+							count = names.len(),
+							names = names.join("\n")
+						),
+						&tr!("dialog.not_now"),
+						&tr!("dialog.download"),
+						"",
+					);
+
+					if choice != Some(1) {
+						continue;
+					}
+
+					let mut app_state_locked = app_state.write().await;
+					let changed = download_list.set_checked_names(&names, true);
+					apply_optional_changes(&profiles_map, &mut app_state_locked, changed).await;
+				}
+				Events::PresetChosen => {
+					let index = preset_choice.value();
+					// INFO: first item is only the placeholder
+					if index <= 0 {
+						continue;
+					}
+					preset_choice.set_value(0);
+
+					let app_state_locked = app_state.read().await;
+					let name = app_state_locked
+						.branch_info
 						.as_ref()
-						.and_then(|v| profiles_map.get_mut_profile(v))
-					{
-						if let Some(branch_name) = app_state_locked.branch_name.as_ref() {
-							if !profile.keep_mods_in_branch.contains_key(branch_name) {
-								profile
-									.keep_mods_in_branch
-									.insert(branch_name.clone(), Vec::new());
-							}
+						.and_then(|v| v.presets.get(index as usize - 1))
+						.map(|v| v.name.clone());
 
-							let mut keep_mods_branch =
-								profile.keep_mods_in_branch.get_mut(branch_name).unwrap();
+					if let Some(name) = name {
+						fltk_tx.send(Events::ApplyPreset(name));
+					}
+				}
+				Events::ApplyPreset(name) => {
+					let mut app_state_locked = app_state.write().await;
+
+					let presets = match app_state_locked.branch_info.as_ref() {
+						Some(branch_info) => &branch_info.presets,
+						None => continue,
+					};
+					let mods = match api::Preset::find(presets, &name) {
+						Some(preset) => preset.mods.clone(),
+						None => {
+							fltk_tx.send(Events::Alert(preview::unknown_preset(presets, &name)));
+							continue;
+						}
+					};
 
-							let index = keep_mods_branch.value().iter().position(|n| *n == modname);
+					// INFO: installed optionals outside the preset stay, they are in the delete list
+					let changed = download_list.select_only(widgets::EntryKind::Optional, &mods);
+					apply_optional_changes(&profiles_map, &mut app_state_locked, changed).await;
+				}
+				Events::ShowModDetails(name) => {
+					let app_state_locked = app_state.read().await;
 
-							if is_checked && index.is_some() {
-								keep_mods_branch.swap_remove(index.unwrap());
-							} else if !is_checked && index.is_none() {
-								keep_mods_branch.push(modname.clone());
-							}
+					let remote = app_state_locked
+						.branch_info
+						.as_ref()
+						.and_then(|v| v.mods.iter().find(|v| v.name == name))
+						.cloned();
+					let local = app_state_locked
+						.mods_path
+						.as_ref()
+						.map(|v| v.join(&name))
+						.filter(|v| v.is_file());
+					let source = app_state_locked
+						.server_main_address
+						.as_ref()
+						.zip(app_state_locked.branch_name.as_ref())
+						.and_then(|(address, branch)| api::local_mod_path(address, branch, &name));
+
+					drop(app_state_locked);
+
+					tokio::spawn(async move {
+						let details = details::collect(&name, remote.as_ref(), local, source).await;
+						fltk_tx.send(Events::ModDetails(details));
+					});
+				}
+				Events::ModDetails(details) => {
+					details_buffer.set_text(&details.text);
+
+					match details
+						.icon
+						.as_ref()
+						.and_then(|v| image::PngImage::from_data(v).ok())
+					{
+						Some(mut icon) => {
+							icon.scale(64, 64, true, true);
+							details_icon.set_image(Some(icon));
+							details_icon.show();
+						}
+						None => details_icon.hide(),
+					}
+
+					match details.homepage {
+						Some(homepage) => {
+							homepage_button.set_tooltip(&homepage);
+							homepage_button.show();
 						}
+						None => homepage_button.hide(),
 					}
+
+					details_flex.layout();
+					details_flex.redraw();
 				}
 				Events::Alert(text) => {
-					dialog::message_title("Alert!");
+					dialog::message_title(&tr!("alert.title"));
 					dialog::alert_default(&text);
 				}
+				Events::Message(text) => {
+					dialog::message_title(&tr!("message.title"));
+					dialog::message_default(&text);
+				}
+				Events::WatchChanges {
+					changes,
+					branch_names,
+					branch_info,
+				} => {
+					// INFO: applying now would fight the download for app state, newer changes win
+					if is_downloading.load(Ordering::Relaxed) {
+						banner.set_label(&changes.to_string());
+						banner.show();
+						flex.layout();
+
+						let (prev_branch_names, prev_branch_info) =
+							pending_watch.take().map(|v| (v.1, v.2)).unwrap_or_default();
+						pending_watch = Some((
+							changes,
+							branch_names.or(prev_branch_names),
+							branch_info.or(prev_branch_info),
+						));
+						continue;
+					}
+
+					if let Some(branch_names) = branch_names {
+						let mut app_state_locked = app_state.write().await;
+
+						let current = branch_chooser.choice();
+						branch_chooser.clear();
+						for branch_name in branch_names.iter() {
+							branch_chooser.add_choice(branch_name);
+						}
+
+						let i = current.map(|v| branch_chooser.find_index(&v)).unwrap_or(-1);
+						if i >= 0 {
+							branch_chooser.set_value(i);
+						} else {
+							// INFO: current branch got removed
+							branch_chooser.set_value(0);
+							fltk_tx.send(Events::GetMods);
+						}
+						branch_chooser.set_damage(true);
+
+						app_state_locked.branch_names = branch_names;
+					}
+
+					if let Some(branch_info) = branch_info {
+						fltk_tx.send(Events::ModsResult(branch_info));
+
+						if watcher::get_settings().auto_sync {
+							fltk_tx.send(Events::Download);
+						}
+					}
+
+					banner.set_label(&changes.to_string());
+					banner.show();
+					flex.layout();
+				}
+				Events::BannerDismiss => {
+					banner.hide();
+					flex.layout();
+				}
 
 				// Download events
 				Events::ShowDownloadModal { total_size } => {
-					file_count_label.set_label(&"0/0");
-					total_progress.set_label("Total progress 0%");
+					strategy_label.set_label("");
+					limit_input.set_value(&(throttle::get_limit().unwrap_or(0) / 1000).to_string());
+					file_count_label.set_label(&tr!("download.files", count = 0, done = 0));
+					total_progress.set_label(&tr!("download.total_progress", percent = 0));
 					total_progress.set_maximum(total_size as f64);
 					total_progress.set_value(0.0);
 					download_wind.show();
 				}
+				Events::DownloadStrategy(text) => {
+					strategy_label.set_label(&text);
+				}
 				Events::DownloadNewFile {
 					title,
 					size,
 					count,
 					total_file_count,
 				} => {
+					current_file_count = count;
 					filename_label.set_label(&title);
-					file_count_label.set_label(&format!("{}/{}", count, total_file_count));
+					file_count_label.set_label(&tr!(
+						"download.files",
+						count = total_file_count,
+						done = count
+					));
 					download_speed_label.set_label("0 B/s");
 					current_progress.set_value(0.0);
-					current_progress.set_label("Current progress 0%");
+					current_progress.set_label(&tr!("download.current_progress", percent = 0));
 					current_progress.set_maximum(size as f64);
 				}
 				// TODO: pass total, current downloaded chunk instead of calculating here
-				Events::DownloadProgess { downloaded_chunk } => {
+				Events::DownloadProgess {
+					count,
+					downloaded_chunk,
+				} => {
 					// INFO: add chunk size to progress bars value
-
-					current_progress.set_value(current_progress.value() + downloaded_chunk as f64);
-					current_progress.set_label(&format!(
-						"Current progress {:.2}%",
-						current_progress.value() / current_progress.maximum() * 100.0
-					));
+					// files download concurrently, current progress only follows the newest one
+
+					if count == current_file_count {
+						current_progress
+							.set_value(current_progress.value() + downloaded_chunk as f64);
+						current_progress.set_label(&tr!(
+							"download.current_progress",
+							percent = format!(
+								"{:.2}",
+								current_progress.value() / current_progress.maximum() * 100.0
+							)
+						));
+					}
 
 					total_progress.set_value(total_progress.value() + downloaded_chunk as f64);
-					total_progress.set_label(&format!(
-						"Total progress {:.2}%",
-						total_progress.value() / total_progress.maximum() * 100.0
+					total_progress.set_label(&tr!(
+						"download.total_progress",
+						percent = format!(
+							"{:.2}",
+							total_progress.value() / total_progress.maximum() * 100.0
+						)
 					));
 				}
 				Events::DownloadSpeedMeter { bytes_per_s } => {
@@ -837,6 +2180,30 @@ async fn main() {
 				Events::DownloadCancel => {
 					let _ = progress_stop_tx.send(true).await;
 				}
+				Events::DownloadLimit => {
+					let limit = match limit_input.value().trim().parse::<u64>() {
+						Ok(kbps) => (kbps > 0).then_some(kbps * 1000),
+						Err(_) => {
+							fltk_tx.send(Events::Alert(tr!("alert.speed_limit_number")));
+							continue;
+						}
+					};
+
+					throttle::set_limit(limit);
+
+					// INFO: download holds a read lock, so this won't block
+					if let Ok(app_state_locked) = app_state.try_read()
+						&& let Some(mut profile) = app_state_locked
+							.profile_name
+							.as_ref()
+							.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						profile.download_limit = limit;
+						// INFO: save_profiles goes through every profile, can't hold this one
+						drop(profile);
+						profiles::save_profiles(&profiles_map).await;
+					}
+				}
 				Events::DeleteMods => {
 					let app_state = app_state.clone();
 
@@ -863,21 +2230,519 @@ async fn main() {
 				Events::MenuAbout => {
 					about_win.show();
 				}
-				Events::MenuSettings => {}
+				Events::MenuSettings => {
+					let settings = settings::get();
+
+					align_choice.set_value(match settings.label_align {
+						settings::LabelAlign::Left => 0,
+						settings::LabelAlign::Right => 1,
+					});
+					theme_names = theme::names();
+					theme_choice.clear();
+					for name in theme_names.iter() {
+						theme_choice.add_choice(&name.replace('/', "\\/").replace('|', " "));
+					}
+					theme_choice.set_value(
+						theme_names
+							.iter()
+							.position(|v| *v == settings.theme)
+							.unwrap_or(0) as i32,
+					);
+					language_choice.set_value(
+						settings::LANGUAGES
+							.iter()
+							.position(|v| v.0 == settings.language)
+							.unwrap_or(0) as i32,
+					);
+					auto_save_check.set_checked(settings.auto_save_on_exit);
+					check_updates_check.set_checked(settings.check_updates);
+					concurrency_input.set_value(&settings.download_concurrency.to_string());
+					cache_dir_input.set_value(settings.cache_dir.as_deref().unwrap_or_default());
+					cache_size_input
+						.set_value(&(settings.cache_size_limit / 1_000_000).to_string());
+					cache_usage_label.set_label(&tr!(
+						"prefs.cache_usage",
+						size = utils::readable_size(cache::size().await as f64)
+					));
+
+					prefs_win.show();
+				}
+				Events::PreferencesBrowseCache => {
+					if let Some(path) = dialog::dir_chooser(&tr!("prefs.choose_cache"), "", false) {
+						cache_dir_input.set_value(&path);
+					}
+				}
+				Events::PreferencesSave => {
+					let concurrency = match concurrency_input.value().trim().parse::<usize>() {
+						Ok(v) if v > 0 => v,
+						_ => {
+							fltk_tx.send(Events::Alert(tr!("alert.concurrency_number")));
+							continue;
+						}
+					};
+					let cache_size_mb = match cache_size_input.value().trim().parse::<u64>() {
+						Ok(v) => v,
+						Err(_) => {
+							fltk_tx.send(Events::Alert(tr!("alert.cache_size_number")));
+							continue;
+						}
+					};
+
+					let cache_dir = cache_dir_input.value().trim().to_string();
+
+					let mut settings = settings::get();
+					settings.label_align = if align_choice.value() == 1 {
+						settings::LabelAlign::Right
+					} else {
+						settings::LabelAlign::Left
+					};
+					if let Some(name) = theme_names.get(theme_choice.value().max(0) as usize) {
+						settings.theme = name.clone();
+					}
+					let language = settings::LANGUAGES[language_choice.value().max(0) as usize]
+						.0
+						.to_string();
+					if language != settings.language {
+						// INFO: menu items are looked up by their translated paths, relabeling them in place keeps that working
+						let relabel = i18n::switch_language(&language);
+						widgets::relabel_menu(&menubar, &relabel);
+						for win in [
+							&main_wind,
+							&download_wind,
+							&about_win,
+							&login_win,
+							&network_win,
+							&keep_win,
+							&prefs_win,
+							&preview_win,
+						] {
+							widgets::relabel(win, &relabel);
+						}
+					}
+					settings.language = language;
+					settings.auto_save_on_exit = auto_save_check.is_checked();
+					settings.check_updates = check_updates_check.is_checked();
+					settings.download_concurrency = concurrency;
+					settings.cache_dir = (!cache_dir.is_empty()).then_some(cache_dir);
+					settings.cache_size_limit = cache_size_mb * 1_000_000;
+
+					if let Err(err) =
+						cache::configure(settings.cache_dir(), settings.cache_size_limit).await
+					{
+						fltk_tx.send(Events::Alert(tr!("alert.cache_dir", error = err)));
+						continue;
+					}
+
+					prefs_win.hide();
+
+					// INFO: apply everything live
+					theme::apply(theme::find(&settings.theme));
+					download_list.refresh();
+					delete_list.refresh();
+					apply_label_align(settings.label_align.to_align(), &mut label_rows);
+					banner.set_align(settings.label_align.to_align());
+					flex.layout();
+					app::redraw();
+
+					settings::set(settings).await;
+				}
+				Events::PreferencesCancel => {
+					prefs_win.hide();
+				}
+				Events::MenuLogout => {
+					let app_state_locked = app_state.read().await;
+
+					if let Some(profile_name) = app_state_locked.profile_name.as_ref()
+						&& let Some(mut profile) = profiles_map.get_mut_profile(profile_name)
+					{
+						auth::delete_secret(profile_name);
+						profile.auth = None;
+					}
+
+					profiles::save_profiles(&profiles_map).await;
+					api::set_credentials(None);
+				}
+				Events::MenuNetwork => {
+					let app_state_locked = app_state.read().await;
+
+					let settings = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_profile(v))
+						.map(|v| v.network.clone())
+						.unwrap_or_default();
+
+					proxy_input.set_value(settings.proxy.as_deref().unwrap_or_default());
+					ca_input.set_value(settings.ca_bundle.as_deref().unwrap_or_default());
+					pin_input.set_value(settings.pinned_fingerprint.as_deref().unwrap_or_default());
+					connect_timeout_input.set_value(&settings.connect_timeout_secs.to_string());
+					read_timeout_input.set_value(&settings.read_timeout_secs.to_string());
+
+					network_win.show();
+				}
+				Events::NetworkBrowseCa => {
+					if let Some(path) =
+						dialog::file_chooser(&tr!("network.choose_ca"), "*.{pem,crt}", "", false)
+					{
+						ca_input.set_value(&path);
+					}
+				}
+				Events::NetworkSave => {
+					let optional = |v: String| {
+						let v = v.trim().to_string();
+						(!v.is_empty()).then_some(v)
+					};
+					let defaults = NetworkSettings::default();
+
+					let settings = NetworkSettings {
+						proxy: optional(proxy_input.value()),
+						ca_bundle: optional(ca_input.value()),
+						pinned_fingerprint: optional(pin_input.value()),
+						connect_timeout_secs: connect_timeout_input
+							.value()
+							.parse()
+							.unwrap_or(defaults.connect_timeout_secs),
+						read_timeout_secs: read_timeout_input
+							.value()
+							.parse()
+							.unwrap_or(defaults.read_timeout_secs),
+					};
+
+					if let Err(err) = api::set_network_settings(&settings) {
+						fltk_tx.send(Events::Alert(tr!("alert.network_invalid", error = err)));
+						continue;
+					}
+
+					network_win.hide();
+
+					let mut app_state_locked = app_state.write().await;
+
+					if let Some(mut profile) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						profile.network = settings;
+					}
+
+					profiles::save_profiles(&profiles_map).await;
+
+					// INFO: force refetching branches through the new settings
+					app_state_locked.server_main_address = None;
+					fltk_tx.send(Events::GetBranches);
+				}
+				Events::NetworkCancel => {
+					network_win.hide();
+				}
+				Events::MenuRequireSignature | Events::MenuForgetKey => {
+					let app_state_locked = app_state.read().await;
+
+					let required = menubar
+						.find_item(&profiles_menu(&tr!("menu.require_signature")))
+						.is_some_and(|v| v.value());
+
+					if let Some(mut profile) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						profile.require_signature = required;
+						if let Events::MenuForgetKey = val {
+							profile.trusted_key = None;
+						}
+
+						api::set_signature_policy(SignaturePolicy {
+							required: profile.require_signature,
+							trusted_key: profile.trusted_key.clone(),
+						});
+					}
+
+					profiles::save_profiles(&profiles_map).await;
+
+					fltk_tx.send(Events::GetMods);
+				}
+				Events::MenuWatch => {
+					let app_state_locked = app_state.read().await;
+
+					let is_set = |path: &str| menubar.find_item(path).is_some_and(|v| v.value());
+					let settings = watcher::WatchSettings {
+						enabled: is_set(&profiles_menu(&tr!("menu.watch"))),
+						auto_sync: is_set(&profiles_menu(&tr!("menu.auto_sync"))),
+						..watcher::get_settings()
+					};
+
+					if let Some(mut profile) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						profile.watch = settings.clone();
+					}
+
+					watcher::set_settings(settings);
+					profiles::save_profiles(&profiles_map).await;
+				}
+				Events::MenuMirrors => {
+					let mut app_state_locked = app_state.write().await;
+
+					let profile_name = match app_state_locked.profile_name.clone() {
+						Some(profile_name) => profile_name,
+						None => continue,
+					};
+					let current = profiles_map
+						.get_profile(&profile_name)
+						.map(|v| v.mirrors.join(", "))
+						.unwrap_or_default();
+
+					let input = match dialog::input_default(&tr!("dialog.mirrors"), &current) {
+						Some(input) => input,
+						None => continue,
+					};
+
+					let mirrors: Vec<String> = input
+						.split(',')
+						.map(|v| v.trim().to_string())
+						.filter(|v| !v.is_empty())
+						.collect();
+
+					if let Some(mut profile) = profiles_map.get_mut_profile(&profile_name) {
+						profile.mirrors = mirrors;
+					}
+
+					profiles::save_profiles(&profiles_map).await;
+
+					// INFO: force refetching branches with the new mirrors
+					app_state_locked.server_main_address = None;
+					fltk_tx.send(Events::GetBranches);
+				}
+				Events::MenuKeepRules => {
+					let app_state_locked = app_state.read().await;
+
+					let rules = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_profile(v))
+						.map(|v| v.keep_rules.clone())
+						.unwrap_or_default();
+
+					show_keep_rules(&mut keep_browser, &rules);
+					keep_pattern_input.set_value("");
+					keep_size_input.set_value("");
+					keep_age_input.set_value("");
+
+					// INFO: "this branch" makes no sense without one
+					if app_state_locked.branch_name.is_some() {
+						keep_scope_choice.activate();
+					} else {
+						keep_scope_choice.set_value(0);
+						keep_scope_choice.deactivate();
+					}
+
+					keep_win.show();
+				}
+				Events::KeepRuleAdd => {
+					let pattern = keep_pattern_input.value().trim().to_string();
+					if pattern.is_empty() {
+						fltk_tx.send(Events::Alert(tr!("alert.keep_pattern_empty")));
+						continue;
+					}
+
+					let app_state_locked = app_state.read().await;
+
+					let rule = keep::KeepRule {
+						pattern,
+						target: match keep_target_choice.value() {
+							1 => keep::RuleTarget::ModId,
+							_ => keep::RuleTarget::FileName,
+						},
+						branch: match keep_scope_choice.value() {
+							1 => app_state_locked.branch_name.clone(),
+							_ => None,
+						},
+						max_size: keep_size_input
+							.value()
+							.parse::<u64>()
+							.ok()
+							.map(|v| v * 1000 * 1000),
+						max_age_days: keep_age_input.value().parse().ok(),
+					};
+
+					if let Some(mut profile) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						if !profile.keep_rules.contains(&rule) {
+							profile.keep_rules.push(rule);
+						}
+						show_keep_rules(&mut keep_browser, &profile.keep_rules);
+					}
+
+					keep_pattern_input.set_value("");
+					keep_size_input.set_value("");
+					keep_age_input.set_value("");
+				}
+				Events::KeepRuleRemove => {
+					let index = keep_browser.value();
+					if index <= 0 {
+						continue;
+					}
+
+					let app_state_locked = app_state.read().await;
+
+					if let Some(mut profile) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						if (index as usize) <= profile.keep_rules.len() {
+							profile.keep_rules.remove(index as usize - 1);
+						}
+						show_keep_rules(&mut keep_browser, &profile.keep_rules);
+					}
+				}
+				Events::KeepRulesClose => {
+					keep_win.hide();
+
+					profiles::save_profiles(&profiles_map).await;
+
+					// INFO: list the mods again so the to delete list follows the rules
+					let app_state_locked = app_state.read().await;
+					if let Some(branch_info) = app_state_locked.branch_info.clone() {
+						fltk_tx.send(Events::ModsResult(branch_info));
+					}
+				}
+				Events::MenuInstallMode(mode) => {
+					let mut app_state_locked = app_state.write().await;
+
+					app_state_locked.install_mode = mode;
+
+					if let Some(mut profile) = app_state_locked
+						.profile_name
+						.as_ref()
+						.and_then(|v| profiles_map.get_mut_profile(v))
+					{
+						profile.install_mode = mode;
+					}
+
+					profiles::save_profiles(&profiles_map).await;
+				}
+				Events::MenuClearCache => {
+					let size = cache::size().await;
+
+					let choice = dialog::choice2_default(
+						&tr!(
+							"dialog.clear_cache",
+							size = utils::readable_size(size as f64)
+						),
+						&tr!("common.cancel"),
+						&tr!("dialog.clear"),
+						"",
+					);
+
+					if choice != Some(1) {
+						continue;
+					}
+
+					cache::clear().await;
+					dialog::message_default(&tr!("message.cache_cleared"));
+				}
+				Events::MenuScanMods => {
+					let mods_path = match app_state.read().await.mods_path.clone() {
+						Some(mods_path) => mods_path,
+						None => {
+							fltk_tx.send(Events::Alert(tr!("alert.select_mods_folder")));
+							continue;
+						}
+					};
+
+					tokio::spawn(async move {
+						let local_mods = match syncer::get_local_mods(&mods_path) {
+							Ok(local_mods) => local_mods,
+							Err(err) => {
+								fltk_tx.send(Events::Alert(tr!(
+									"alert.read_mods_folder",
+									error = err
+								)));
+								return;
+							}
+						};
+
+						let mut report = Vec::new();
+						for name in local_mods.iter() {
+							let path = mods_path.join(name);
+
+							let scan_report = match scanner::scan_jar_async(&path).await {
+								Ok(scan_report) => scan_report,
+								Err(err) => {
+									println!("couldn't scan {}: {}", name, err);
+									continue;
+								}
+							};
+
+							if scan_report.is_clean() {
+								continue;
+							}
+
+							// INFO: heuristics alone can't tell a native library from malware, only report them
+							if !scan_report.is_malicious() {
+								report.push(tr!(
+									"scan.suspicious",
+									name = name,
+									findings = scan_report.suspicious.join(", ")
+								));
+								continue;
+							}
+
+							match scanner::quarantine(&path, name, &scan_report.findings()).await {
+								Ok(_) => report.push(tr!("scan.quarantined", name = name)),
+								Err(err) => report.push(tr!(
+									"scan.quarantine_failed",
+									name = name,
+									error = err
+								)),
+							}
+						}
+
+						if report.is_empty() {
+							fltk_tx
+								.send(Events::Message(tr!("scan.clean", count = local_mods.len())));
+						} else {
+							fltk_tx.send(Events::Alert(tr!(
+								"scan.found",
+								count = local_mods.len(),
+								found = report.len(),
+								report = report.join("\n")
+							)));
+							fltk_tx.send(Events::GetMods);
+						}
+					});
+				}
+				Events::MenuUpdateSignatures => {
+					tokio::spawn(async move {
+						match scanner::update_signatures().await {
+							Ok(version) => fltk_tx.send(Events::Message(tr!(
+								"signatures.updated",
+								version = version
+							))),
+							Err(err) => {
+								fltk_tx.send(Events::Alert(tr!("signatures.failed", error = err)))
+							}
+						}
+					});
+				}
 				Events::MenuProfile(name) => {
 					let mut app_state_locked = app_state.write().await;
 
 					if let Some(prev_profile_name) = app_state_locked.profile_name.as_ref() {
 						if let Some(mut prev_item) =
-							menubar.find_item(&format!("&File/Profiles/{}", prev_profile_name))
+							menubar.find_item(&profiles_menu(prev_profile_name))
 						{
 							prev_item.set_label_color(enums::Color::Black);
 						}
 					}
 
-					let mut item = menubar
-						.find_item(&format!("&File/Profiles/{}", &name))
-						.unwrap();
+					let mut item = menubar.find_item(&profiles_menu(&name)).unwrap();
 					item.set_label_color(enums::Color::Red);
 
 					let profile = profiles_map.get_profile(&name).unwrap();
@@ -906,12 +2771,76 @@ async fn main() {
 						branch_chooser.set_value(i);
 					}
 
+					app_state_locked.install_mode = profile.install_mode;
+
+					if let Err(err) = api::set_network_settings(&profile.network) {
+						fltk_tx.send(Events::Alert(tr!(
+							"alert.profile_network_invalid",
+							error = err
+						)));
+						let _ = api::set_network_settings(&NetworkSettings::default());
+					}
+
+					api::set_signature_policy(SignaturePolicy {
+						required: profile.require_signature,
+						trusted_key: profile.trusted_key.clone(),
+					});
+					if let Some(mut item) =
+						menubar.find_item(&profiles_menu(&tr!("menu.require_signature")))
+					{
+						if profile.require_signature {
+							item.set();
+						} else {
+							item.clear();
+						}
+					}
+
+					watcher::set_settings(profile.watch.clone());
+					for (path, value) in [
+						(&profiles_menu(&tr!("menu.watch")), profile.watch.enabled),
+						(
+							&profiles_menu(&tr!("menu.auto_sync")),
+							profile.watch.auto_sync,
+						),
+					] {
+						if let Some(mut item) = menubar.find_item(path) {
+							if value {
+								item.set();
+							} else {
+								item.clear();
+							}
+						}
+					}
+
+					let credentials = profile.auth.clone().and_then(|kind| {
+						auth::get_secret(&name).map(|secret| Credentials { kind, secret })
+					});
+					api::set_credentials(credentials);
+
+					// INFO: command line limit wins over profile's
+					if args.limit_rate.is_none() {
+						throttle::set_limit(profile.download_limit);
+					}
+					for mode in InstallMode::ALL {
+						if let Some(mut item) = menubar.find_item(&file_menu(&format!(
+							"{}/{}",
+							tr!("menu.install_mode"),
+							mode.label()
+						))) {
+							if mode == profile.install_mode {
+								item.set();
+							} else {
+								item.clear();
+							}
+						}
+					}
+
 					fltk_tx.send(Events::GetBranches);
 
 					app_state_locked.profile_name = Some(name);
 				}
 				Events::MenuNewProfile => {
-					let name = dialog::input_default("Name for new profile:", "")
+					let name = dialog::input_default(&tr!("dialog.new_profile"), "")
 						.map(|v| String::from(v.trim()));
 
 					if name.is_none() {
@@ -921,19 +2850,19 @@ async fn main() {
 					let name = name.unwrap();
 
 					if name.len() == 0 {
-						fltk_tx.send(Events::Alert(String::from("Name cannot be empty")));
+						fltk_tx.send(Events::Alert(tr!("alert.name_empty")));
 						continue;
 					}
 
 					if profiles_map.profile_exists(&name) {
-						fltk_tx.send(Events::Alert(format!("Profile '{}' already exists", &name)));
+						fltk_tx.send(Events::Alert(tr!("alert.profile_exists", name = name)));
 						continue;
 					}
 
 					fltk_tx.send(Events::MenuSaveProfile(name.clone()));
 
 					let default_profile_index =
-						menubar.find_index(&format!("&File/Profiles/{}", DEFAULT_PROFILE_NAME));
+						menubar.find_index(&profiles_menu(DEFAULT_PROFILE_NAME));
 
 					let new_index = menubar.insert_emit(
 						default_profile_index,
@@ -948,7 +2877,7 @@ async fn main() {
 
 					if let Some(prev_profile_name) = app_state_locked.profile_name.as_ref() {
 						if let Some(mut prev_item) =
-							menubar.find_item(&format!("&File/Profiles/{}", prev_profile_name))
+							menubar.find_item(&profiles_menu(prev_profile_name))
 						{
 							prev_item.set_label_color(enums::Color::Black);
 						}
@@ -957,14 +2886,14 @@ async fn main() {
 					let mut item = menubar.at(new_index).unwrap();
 					item.set_label_color(enums::Color::Red);
 
-					dialog::message_default(&format!("Successfully created '{}' profile", &name));
+					dialog::message_default(&tr!("message.profile_created", name = name));
 
 					app_state_locked.profile_name = Some(name);
 
 					ip_ok_button.do_callback();
 				}
 				Events::MenuDeleteProfile => {
-					let name = dialog::input_default("Name of profile to delete:", "")
+					let name = dialog::input_default(&tr!("dialog.delete_profile"), "")
 						.map(|v| String::from(v.trim()));
 
 					if name.is_none() {
@@ -974,18 +2903,19 @@ async fn main() {
 					let name = name.unwrap();
 
 					if name == DEFAULT_PROFILE_NAME {
-						fltk_tx.send(Events::Alert(String::from("Good try")));
+						fltk_tx.send(Events::Alert(tr!("alert.good_try")));
 						continue;
 					}
 
 					if !profiles_map.profile_exists(&name) {
-						fltk_tx.send(Events::Alert(format!("Profile '{}' doesn't exists", &name)));
+						fltk_tx.send(Events::Alert(tr!("alert.profile_missing", name = name)));
 						continue;
 					}
 
 					profiles_map.delete_profile(&name);
+					auth::delete_secret(&name);
 
-					let i = menubar.find_index(&format!("&File/Profiles/{}", &name));
+					let i = menubar.find_index(&profiles_menu(&name));
 					menubar.remove(i);
 
 					let app_state_locked = app_state.read().await;
@@ -1000,7 +2930,7 @@ async fn main() {
 
 					profiles::save_profiles(&profiles_map).await;
 
-					dialog::message_default(&format!("Successfully deleted '{}' profile", &name));
+					dialog::message_default(&tr!("message.profile_deleted", name = name));
 				}
 				Events::MenuSaveProfile(name) => {
 					let app_state_locked = app_state.read().await;
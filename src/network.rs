@@ -0,0 +1,204 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{Certificate, Client, Proxy};
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::tr;
+
+/// Per profile network settings, used for every request to the hoster
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct NetworkSettings {
+	/// http://, https:// or socks5:// proxy url
+	pub proxy: Option<String>,
+	/// Path to a PEM file with extra trusted certificates (e.g. self-signed one of the hoster)
+	pub ca_bundle: Option<String>,
+	/// Hex sha256 of the server's certificate, if set only that certificate is accepted
+	pub pinned_fingerprint: Option<String>,
+	pub connect_timeout_secs: u64,
+	/// Maximum wait between two received chunks
+	pub read_timeout_secs: u64,
+}
+
+impl Default for NetworkSettings {
+	fn default() -> Self {
+		Self {
+			proxy: None,
+			ca_bundle: None,
+			pinned_fingerprint: None,
+			connect_timeout_secs: 2,
+			read_timeout_secs: 30,
+		}
+	}
+}
+
+/// Accepts only the certificate with the pinned fingerprint from the pinned host, ignoring the CA chain
+/// (so self-signed certificates work too)
+#[derive(Debug)]
+struct PinnedCertVerifier {
+	fingerprint: Vec<u8>,
+	/// Only this host's certificate is pinned, None pins nothing
+	host: Option<ServerName<'static>>,
+	/// Checks other hosts against the CA bundle, None if there is no bundle
+	others: Option<Arc<WebPkiServerVerifier>>,
+	provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+	fn verify_server_cert(
+		&self,
+		end_entity: &CertificateDer<'_>,
+		intermediates: &[CertificateDer<'_>],
+		server_name: &ServerName<'_>,
+		ocsp_response: &[u8],
+		now: UnixTime,
+	) -> Result<ServerCertVerified, rustls::Error> {
+		// INFO: mirrors and other sites have their own certificates
+		if self.host.as_ref() != Some(server_name) {
+			return match &self.others {
+				Some(others) => others.verify_server_cert(
+					end_entity,
+					intermediates,
+					server_name,
+					ocsp_response,
+					now,
+				),
+				None => Err(rustls::Error::InvalidCertificate(
+					CertificateError::UnknownIssuer,
+				)),
+			};
+		}
+
+		if Sha256::digest(end_entity.as_ref()).as_slice() == self.fingerprint {
+			Ok(ServerCertVerified::assertion())
+		} else {
+			Err(rustls::Error::General(tr!("network.pin_mismatch")))
+		}
+	}
+
+	fn verify_tls12_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, rustls::Error> {
+		verify_tls12_signature(
+			message,
+			cert,
+			dss,
+			&self.provider.signature_verification_algorithms,
+		)
+	}
+
+	fn verify_tls13_signature(
+		&self,
+		message: &[u8],
+		cert: &CertificateDer<'_>,
+		dss: &DigitallySignedStruct,
+	) -> Result<HandshakeSignatureValid, rustls::Error> {
+		verify_tls13_signature(
+			message,
+			cert,
+			dss,
+			&self.provider.signature_verification_algorithms,
+		)
+	}
+
+	fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+		self.provider
+			.signature_verification_algorithms
+			.supported_schemes()
+	}
+}
+
+/// Parse sha256 fingerprint, accepts "AB:CD:..." and "abcd..." forms
+pub fn parse_fingerprint(fingerprint: &str) -> Result<Vec<u8>, String> {
+	let cleaned: String = fingerprint
+		.chars()
+		.filter(|c| !c.is_whitespace() && *c != ':')
+		.collect();
+
+	match hex::decode(&cleaned) {
+		Ok(bytes) if bytes.len() == 32 => Ok(bytes),
+		_ => Err(tr!("network.invalid_pin")),
+	}
+}
+
+/// Build a client with the settings, the fingerprint is only pinned for pinned_host
+/// Errors if proxy url, CA bundle or fingerprint is invalid
+pub fn build_client(
+	settings: &NetworkSettings,
+	pinned_host: Option<&str>,
+) -> Result<Client, String> {
+	let mut builder = Client::builder()
+		.connect_timeout(Duration::from_secs(settings.connect_timeout_secs))
+		.read_timeout(Duration::from_secs(settings.read_timeout_secs));
+
+	if let Some(proxy) = settings.proxy.as_deref().filter(|v| !v.is_empty()) {
+		let proxy = Proxy::all(proxy).map_err(|e| tr!("network.invalid_proxy", error = e))?;
+		builder = builder.proxy(proxy);
+	}
+
+	let mut roots = RootCertStore::empty();
+	if let Some(path) = settings.ca_bundle.as_deref().filter(|v| !v.is_empty()) {
+		let pem = std::fs::read(path).map_err(|e| tr!("network.read_ca", error = e))?;
+		let certs =
+			Certificate::from_pem_bundle(&pem).map_err(|e| tr!("network.invalid_ca", error = e))?;
+
+		for cert in certs {
+			builder = builder.add_root_certificate(cert);
+		}
+
+		// INFO: pinned clients use their own tls config, the bundle has to go there too
+		for cert in CertificateDer::pem_slice_iter(&pem) {
+			let cert = cert.map_err(|e| tr!("network.invalid_ca", error = e))?;
+			roots
+				.add(cert)
+				.map_err(|e| tr!("network.invalid_ca", error = e))?;
+		}
+	}
+
+	if let Some(fingerprint) = settings
+		.pinned_fingerprint
+		.as_deref()
+		.filter(|v| !v.is_empty())
+	{
+		let provider = Arc::new(rustls::crypto::ring::default_provider());
+		let others = if roots.is_empty() {
+			None
+		} else {
+			Some(
+				WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+					.build()
+					.map_err(|e| tr!("network.invalid_ca", error = e))?,
+			)
+		};
+		let verifier = PinnedCertVerifier {
+			fingerprint: parse_fingerprint(fingerprint)?,
+			// INFO: ipv6 hosts of urls are in brackets
+			host: pinned_host
+				.and_then(|v| ServerName::try_from(v.trim_matches(['[', ']']).to_string()).ok()),
+			others,
+			provider: provider.clone(),
+		};
+
+		let tls_config = rustls::ClientConfig::builder_with_provider(provider)
+			.with_safe_default_protocol_versions()
+			.map_err(|e| e.to_string())?
+			.dangerous()
+			.with_custom_certificate_verifier(Arc::new(verifier))
+			.with_no_client_auth();
+
+		builder = builder.use_preconfigured_tls(tls_config);
+	}
+
+	builder.build().map_err(|e| e.to_string())
+}
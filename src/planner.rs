@@ -0,0 +1,298 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{tr, utils};
+
+/// Latency used when the hoster couldn't be measured
+pub const DEFAULT_LATENCY: Duration = Duration::from_millis(150);
+/// Throughput used before any download was measured (5 MB/s)
+const DEFAULT_BYTES_PER_S: f64 = 5_000_000.0;
+/// An interrupted zip has to be downloaded again from the start if the server can't resume it
+const NO_RESUME_PENALTY: f64 = 1.1;
+/// Zip at least this big compared to the branch has its jars stored, not deflated
+const STORED_RATIO: f64 = 0.99;
+/// Speed of getting mods out of the zip, deflated entries are much slower than stored ones
+const INFLATE_BYTES_PER_S: f64 = 150_000_000.0;
+const COPY_BYTES_PER_S: f64 = 500_000_000.0;
+/// Zip isn't used when the cache has more than this share of the wanted mods, it would download them again
+const MAX_CACHED_SHARE: f64 = 0.75;
+
+static LAST_BYTES_PER_S: AtomicU64 = AtomicU64::new(0);
+
+/// Remember the last measured download speed for future plans
+pub fn record_throughput(bytes_per_s: f64) {
+	if bytes_per_s > 0.0 {
+		LAST_BYTES_PER_S.store(bytes_per_s.to_bits(), Ordering::Relaxed);
+	}
+}
+
+fn last_throughput() -> f64 {
+	let bps = f64::from_bits(LAST_BYTES_PER_S.load(Ordering::Relaxed));
+	if bps > 0.0 { bps } else { DEFAULT_BYTES_PER_S }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStrategy {
+	Zip,
+	Files,
+	/// Zip, then the mods changed since it was made one by one
+	Hybrid,
+}
+
+/// Everything the planner needs to know about the download and the connection
+#[derive(Debug, Clone)]
+pub struct PlanInput {
+	/// Number of files that has to be downloaded
+	pub file_count: usize,
+	/// Size of the files that has to be downloaded
+	pub files_size: u64,
+	/// Size of the wanted files the cache already has, the zip contains them anyway
+	pub cached_size: u64,
+	/// Size of every mod in the branch (what the zip contains)
+	pub branch_size: u64,
+	/// None if the server has no zip for the branch
+	pub zip_size: Option<u64>,
+	/// Files (of file_count) changed after the zip was made, these come one by one even with the zip
+	pub stale_count: usize,
+	pub stale_size: u64,
+	pub latency: Duration,
+	pub concurrency: usize,
+	pub supports_ranges: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadPlan {
+	pub strategy: DownloadStrategy,
+	pub reason: String,
+}
+
+impl fmt::Display for DownloadPlan {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self.strategy {
+			DownloadStrategy::Zip => tr!("plan.zip"),
+			DownloadStrategy::Files => tr!("plan.files"),
+			DownloadStrategy::Hybrid => tr!("plan.hybrid"),
+		};
+
+		write!(
+			f,
+			"{}",
+			tr!("plan.downloading", strategy = name, reason = self.reason)
+		)
+	}
+}
+
+/// Cost of downloading count files of size bytes, concurrent requests share the latency
+fn transfer_cost(count: usize, size: u64, input: &PlanInput, bps: f64) -> f64 {
+	let rounds = count.div_ceil(input.concurrency.max(1));
+	rounds as f64 * input.latency.as_secs_f64() + size as f64 / bps
+}
+
+/// Pick the cheapest way to download the mods
+/// Cost of a strategy = request count * latency (shared by concurrent requests) + bytes / throughput
+/// + time to get the mods out of the zip, which depends on how much it is compressed
+pub fn plan_download(input: &PlanInput) -> DownloadPlan {
+	let bps = last_throughput();
+	let concurrency = input.concurrency.max(1);
+
+	let files_cost = transfer_cost(input.file_count, input.files_size, input, bps);
+
+	if input.file_count == 0 {
+		return DownloadPlan {
+			strategy: DownloadStrategy::Files,
+			reason: tr!("plan.nothing"),
+		};
+	}
+
+	let zip_size = match input.zip_size {
+		Some(size) => size,
+		None => {
+			return DownloadPlan {
+				strategy: DownloadStrategy::Files,
+				reason: tr!("plan.no_zip"),
+			};
+		}
+	};
+
+	let wanted_size = input.files_size + input.cached_size;
+	if wanted_size > 0 && input.cached_size as f64 / wanted_size as f64 > MAX_CACHED_SHARE {
+		return DownloadPlan {
+			strategy: DownloadStrategy::Files,
+			reason: tr!("plan.mostly_cached"),
+		};
+	}
+
+	let ratio = if input.branch_size > 0 {
+		zip_size as f64 / input.branch_size as f64
+	} else {
+		1.0
+	};
+
+	// INFO: the whole zip is charged, cached and already installed mods come with it too
+	let mut zip_cost = input.latency.as_secs_f64() + zip_size as f64 / bps;
+	if !input.supports_ranges {
+		zip_cost *= NO_RESUME_PENALTY;
+	}
+
+	let extract_bps = if ratio < STORED_RATIO {
+		INFLATE_BYTES_PER_S
+	} else {
+		COPY_BYTES_PER_S
+	};
+	zip_cost += input.files_size.saturating_sub(input.stale_size) as f64 / extract_bps;
+
+	// INFO: if every file changed since, this alone makes the zip lose to the files
+	zip_cost += transfer_cost(input.stale_count, input.stale_size, input, bps);
+
+	let strategy = match (zip_cost < files_cost, input.stale_count > 0) {
+		(true, false) => DownloadStrategy::Zip,
+		(true, true) => DownloadStrategy::Hybrid,
+		(false, _) => DownloadStrategy::Files,
+	};
+
+	let reason = tr!(
+		"plan.estimate",
+		count = input.file_count,
+		files_time = format!("{:.1}", files_cost),
+		concurrency = concurrency,
+		latency = input.latency.as_millis(),
+		zip_time = format!("{:.1}", zip_cost),
+		ratio = format!("{:.0}", ratio * 100.0),
+		resumable = if input.supports_ranges {
+			String::new()
+		} else {
+			tr!("plan.not_resumable")
+		},
+		stale = if input.stale_count > 0 {
+			tr!("plan.stale", count = input.stale_count)
+		} else {
+			String::new()
+		},
+		speed = utils::readable_bps(bps),
+	);
+
+	DownloadPlan { strategy, reason }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MB: u64 = 1_000_000;
+
+	// INFO: nothing records a throughput here, so every plan uses DEFAULT_BYTES_PER_S
+	fn input() -> PlanInput {
+		PlanInput {
+			file_count: 200,
+			files_size: 400 * MB,
+			cached_size: 0,
+			branch_size: 400 * MB,
+			zip_size: Some(400 * MB),
+			stale_count: 0,
+			stale_size: 0,
+			latency: DEFAULT_LATENCY,
+			concurrency: 4,
+			supports_ranges: true,
+		}
+	}
+
+	#[test]
+	fn transfer_cost_shares_latency_between_concurrent_requests() {
+		let input = input();
+		let latency = input.latency.as_secs_f64();
+
+		let one = transfer_cost(1, 0, &input, DEFAULT_BYTES_PER_S);
+		let four = transfer_cost(4, 0, &input, DEFAULT_BYTES_PER_S);
+		let five = transfer_cost(5, 0, &input, DEFAULT_BYTES_PER_S);
+		assert_eq!(one, latency);
+		assert_eq!(four, latency);
+		assert_eq!(five, 2.0 * latency);
+
+		let sized = transfer_cost(4, 5 * MB, &input, DEFAULT_BYTES_PER_S);
+		assert!((sized - (latency + 1.0)).abs() < 1e-9);
+	}
+
+	#[test]
+	fn whole_branch_uses_zip() {
+		assert_eq!(plan_download(&input()).strategy, DownloadStrategy::Zip);
+	}
+
+	#[test]
+	fn mods_newer_than_zip_use_hybrid() {
+		let plan = plan_download(&PlanInput {
+			stale_count: 4,
+			stale_size: 8 * MB,
+			..input()
+		});
+		assert_eq!(plan.strategy, DownloadStrategy::Hybrid);
+	}
+
+	#[test]
+	fn few_mods_use_files() {
+		let plan = plan_download(&PlanInput {
+			file_count: 3,
+			files_size: 10 * MB,
+			..input()
+		});
+		assert_eq!(plan.strategy, DownloadStrategy::Files);
+	}
+
+	#[test]
+	fn every_mod_stale_uses_files() {
+		let plan = plan_download(&PlanInput {
+			stale_count: 200,
+			stale_size: 400 * MB,
+			..input()
+		});
+		assert_eq!(plan.strategy, DownloadStrategy::Files);
+	}
+
+	#[test]
+	fn missing_zip_or_nothing_to_do_use_files() {
+		let no_zip = PlanInput {
+			zip_size: None,
+			..input()
+		};
+		let nothing = PlanInput {
+			file_count: 0,
+			files_size: 0,
+			..input()
+		};
+		assert_eq!(plan_download(&no_zip).strategy, DownloadStrategy::Files);
+		assert_eq!(plan_download(&nothing).strategy, DownloadStrategy::Files);
+	}
+
+	#[test]
+	fn mostly_cached_mods_dont_use_zip() {
+		// INFO: slow requests would make the zip cheaper, but it'd download the cached mods again
+		let slow = PlanInput {
+			latency: Duration::from_secs(2),
+			concurrency: 1,
+			..input()
+		};
+		assert_eq!(plan_download(&slow).strategy, DownloadStrategy::Zip);
+
+		let plan = plan_download(&PlanInput {
+			file_count: 40,
+			files_size: 80 * MB,
+			cached_size: 320 * MB,
+			..slow
+		});
+		assert_eq!(plan.strategy, DownloadStrategy::Files);
+	}
+
+	#[test]
+	fn zip_cost_includes_whole_zip() {
+		// INFO: a zip much bigger than the few missing mods loses even with ranges and no stale files
+		let plan = plan_download(&PlanInput {
+			file_count: 20,
+			files_size: 40 * MB,
+			..input()
+		});
+		assert_eq!(plan.strategy, DownloadStrategy::Files);
+	}
+}
@@ -0,0 +1,400 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::api::{self, Preset};
+use crate::auth::{self, Credentials};
+use crate::planner::{self, DownloadPlan, DownloadStrategy};
+use crate::profiles::ProfilesMap;
+use crate::signing::SignaturePolicy;
+use crate::{AppState, cache, keep, settings, syncer, tr, utils};
+
+/// Mod the plan downloads (or installs from the cache)
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedMod {
+	pub name: String,
+	pub size: u64,
+	pub is_optional: bool,
+	/// Installed from the cache, doesn't have to be downloaded
+	pub cached: bool,
+}
+
+/// Everything pressing Download would do, making it doesn't change anything
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncPlan {
+	pub profile: Option<String>,
+	pub server: String,
+	pub branch: String,
+	pub mods_path: PathBuf,
+	/// Not installed yet
+	pub downloads: Vec<PlannedMod>,
+	/// Installed, but differs from the branch's
+	pub updates: Vec<PlannedMod>,
+	pub deletions: Vec<String>,
+	/// Not in the branch (or installed optionals), but not deleted
+	pub kept: Vec<String>,
+	pub plan: DownloadPlan,
+	/// Bytes coming over the network with the chosen strategy
+	pub transfer_size: u64,
+	/// Zip download can be resumed, only needed for downloading
+	#[serde(skip)]
+	pub supports_ranges: bool,
+}
+
+impl SyncPlan {
+	/// Size of every mod the plan installs, cached ones too
+	pub fn install_size(&self) -> u64 {
+		self.downloads
+			.iter()
+			.chain(self.updates.iter())
+			.fold(0, |acc, x| acc + x.size)
+	}
+
+	pub fn install_count(&self) -> usize {
+		self.downloads.len() + self.updates.len()
+	}
+
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("Failed to convert sync plan to json")
+	}
+
+	pub fn to_markdown(&self) -> String {
+		let mut lines = vec![
+			format!("# {}", tr!("preview.heading", branch = self.branch)),
+			String::new(),
+		];
+
+		if let Some(profile) = self.profile.as_ref() {
+			lines.push(format!("- {}", tr!("preview.profile", name = profile)));
+		}
+		lines.push(format!(
+			"- {}",
+			tr!("preview.server", address = self.server)
+		));
+		lines.push(format!(
+			"- {}",
+			tr!("preview.mods_path", path = self.mods_path.display())
+		));
+		lines.push(format!("- {}", self.plan));
+		lines.push(format!(
+			"- {}",
+			tr!(
+				"preview.transfer",
+				size = utils::readable_size(self.transfer_size as f64)
+			)
+		));
+
+		let mod_line = |v: &PlannedMod| {
+			let mut line = format!("- {} ({})", v.name, utils::readable_size(v.size as f64));
+			if v.is_optional {
+				line.push_str(&format!(", {}", tr!("preview.optional")));
+			}
+			if v.cached {
+				line.push_str(&format!(", {}", tr!("preview.cached")));
+			}
+			line
+		};
+		let sections = [
+			(
+				"preview.downloads",
+				self.downloads.iter().map(mod_line).collect::<Vec<_>>(),
+			),
+			(
+				"preview.updates",
+				self.updates.iter().map(mod_line).collect(),
+			),
+			(
+				"preview.deletions",
+				self.deletions.iter().map(|v| format!("- {}", v)).collect(),
+			),
+			(
+				"preview.kept",
+				self.kept.iter().map(|v| format!("- {}", v)).collect(),
+			),
+		];
+
+		for (key, items) in sections {
+			lines.push(String::new());
+			lines.push(format!("## {}", tr!(key, count = items.len())));
+			if items.is_empty() {
+				lines.push(tr!("preview.none"));
+			}
+			lines.extend(items);
+		}
+
+		lines.join("\n")
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+	Json,
+	Markdown,
+}
+
+impl ExportFormat {
+	pub fn extension(self) -> &'static str {
+		match self {
+			ExportFormat::Json => "json",
+			ExportFormat::Markdown => "md",
+		}
+	}
+
+	pub fn render(self, plan: &SyncPlan) -> String {
+		match self {
+			ExportFormat::Json => plan.to_json(),
+			ExportFormat::Markdown => plan.to_markdown(),
+		}
+	}
+}
+
+/// Alert text for a preset the branch doesn't have
+pub fn unknown_preset(presets: &[Preset], name: &str) -> String {
+	let names: Vec<&str> = presets.iter().map(|v| v.name.as_str()).collect();
+
+	tr!(
+		"alert.unknown_preset",
+		name = name,
+		presets = if names.is_empty() {
+			tr!("alert.no_presets")
+		} else {
+			names.join(", ")
+		}
+	)
+}
+
+/// Work out what Download would do with the current lists
+/// Err is a message for the user
+pub async fn build(state: &AppState) -> Result<SyncPlan, String> {
+	let branch_info = state
+		.branch_info
+		.as_ref()
+		.ok_or_else(|| tr!("alert.set_server"))?;
+	let mods_path = state.mods_path.as_ref().ok_or_else(|| {
+		tr!(
+			"alert.set_mods_folder",
+			path = syncer::get_os_default_mods_folder()
+				.as_ref()
+				.and_then(|e| e.to_str())
+				.map(String::from)
+				.unwrap_or_else(|| tr!("alert.unknown_platform"))
+		)
+	})?;
+
+	let branch_name = state.branch_name.clone().unwrap_or_default();
+	let api_path = state.server_api_address.clone().unwrap_or_default();
+	let main_path = state.server_main_address.clone().unwrap_or_default();
+	let zip_file = &branch_info.zip;
+
+	let mut downloads = Vec::new();
+	let mut updates = Vec::new();
+	// INFO: cached mods don't need the network, plan only with the rest
+	let mut uncached_count = 0;
+	let mut uncached_size = 0;
+	let mut cached_size = 0;
+	// INFO: changed after the zip was made, so the zip's copy is old
+	let mut stale_count = 0;
+	let mut stale_size = 0;
+
+	for mcmod in branch_info
+		.mods
+		.iter()
+		.filter(|v| state.to_download_names.get(&v.name).is_some_and(|v| *v))
+	{
+		let cached = cache::contains(mcmod).await;
+		if cached {
+			cached_size += mcmod.size;
+		} else {
+			uncached_count += 1;
+			uncached_size += mcmod.size;
+
+			if mcmod.mod_date > zip_file.mod_date {
+				stale_count += 1;
+				stale_size += mcmod.size;
+			}
+		}
+
+		let planned = PlannedMod {
+			name: mcmod.name.clone(),
+			size: mcmod.size,
+			is_optional: mcmod.is_optional,
+			cached,
+		};
+		if mods_path.join(&mcmod.name).exists() {
+			updates.push(planned);
+		} else {
+			downloads.push(planned);
+		}
+	}
+
+	let latency = api::measure_latency(&api_path)
+		.await
+		.unwrap_or(planner::DEFAULT_LATENCY);
+	let supports_ranges = zip_file.is_present
+		&& api::mod_zip_supports_ranges(&main_path, &branch_name)
+			.await
+			.unwrap_or(false);
+
+	let plan = planner::plan_download(&planner::PlanInput {
+		file_count: uncached_count,
+		files_size: uncached_size,
+		cached_size,
+		branch_size: branch_info.mods.iter().fold(0, |acc, x| acc + x.size),
+		// INFO: if zip is not present, download all files separately
+		zip_size: zip_file.is_present.then_some(zip_file.size),
+		stale_count,
+		stale_size,
+		latency,
+		concurrency: settings::get().download_concurrency,
+		supports_ranges,
+	});
+
+	let mut deletions = Vec::new();
+	let mut kept = Vec::new();
+	for (name, is_deleted) in state.to_delete_names.iter() {
+		if *is_deleted {
+			deletions.push(name.clone());
+		} else {
+			kept.push(name.clone());
+		}
+	}
+	deletions.sort();
+	kept.sort();
+	downloads.sort_by(|a, b| a.name.cmp(&b.name));
+	updates.sort_by(|a, b| a.name.cmp(&b.name));
+
+	Ok(SyncPlan {
+		profile: state.profile_name.clone(),
+		server: api::active_address().unwrap_or(main_path),
+		branch: branch_name,
+		mods_path: mods_path.clone(),
+		downloads,
+		updates,
+		deletions,
+		kept,
+		transfer_size: match plan.strategy {
+			DownloadStrategy::Zip => zip_file.size,
+			DownloadStrategy::Files => uncached_size,
+			DownloadStrategy::Hybrid => zip_file.size + stale_size,
+		},
+		plan,
+		supports_ranges,
+	})
+}
+
+/// Fill the lists like the gui would for the last used profile
+async fn headless_state(
+	profiles_map: &ProfilesMap,
+	preset: Option<&str>,
+) -> Result<AppState, String> {
+	let name = profiles_map.get_last_profile_name().to_string();
+	let profile = profiles_map
+		.get_profile(&name)
+		.map(|v| v.clone())
+		.ok_or_else(|| tr!("cli.no_profile", name = name))?;
+
+	if profile.address.is_empty() || profile.branch.is_empty() {
+		return Err(tr!("cli.profile_incomplete", name = name));
+	}
+
+	api::set_network_settings(&profile.network)?;
+	api::set_mirrors(&profile.address, &profile.mirrors);
+	api::set_signature_policy(SignaturePolicy {
+		required: profile.require_signature,
+		trusted_key: profile.trusted_key.clone(),
+	});
+	api::set_credentials(
+		profile
+			.auth
+			.clone()
+			.and_then(|kind| auth::get_secret(&name).map(|secret| Credentials { kind, secret })),
+	);
+
+	let mods_path = if profile.mods_path.is_empty() {
+		syncer::try_get_mods_folder().unwrap_or_default()
+	} else {
+		PathBuf::from(&profile.mods_path)
+	};
+	if !syncer::is_mods_folder(&mods_path) {
+		return Err(tr!("alert.not_mods_folder"));
+	}
+
+	let api_address = format!("{}/api", profile.address);
+	let branch_info = api::get_mods_in_branch(&api_address, &profile.branch)
+		.await
+		.map_err(|err| err.to_string())?;
+
+	let selected = match preset {
+		Some(preset) => Preset::find(&branch_info.presets, preset)
+			.map(|v| v.mods.clone())
+			.ok_or_else(|| unknown_preset(&branch_info.presets, preset))?,
+		None => profile
+			.selected_optionals
+			.get(&profile.branch)
+			.map(|v| v.clone())
+			.unwrap_or_default(),
+	};
+
+	let local_mod_names = syncer::get_local_mods(&mods_path).map_err(|err| err.to_string())?;
+	let (to_deletes, to_delete_optionals) =
+		syncer::get_mods_to_delete(&branch_info.mods, &local_mod_names);
+	let to_downloads =
+		syncer::get_mods_to_download(&branch_info.mods, &local_mod_names, &mods_path);
+
+	let mut state = AppState {
+		server_api_address: Some(api_address),
+		server_main_address: Some(profile.address.clone()),
+		branch_name: Some(profile.branch.clone()),
+		mods_path: Some(mods_path.clone()),
+		profile_name: Some(name),
+		install_mode: profile.install_mode,
+		..Default::default()
+	};
+
+	// INFO: saved selections already follow the group rules, the gui enforced them
+	for to_download in to_downloads {
+		let is_checked = !to_download.is_optional || selected.contains(&to_download.name);
+		state.to_download_names.insert(to_download.name, is_checked);
+	}
+	for to_delete in to_deletes {
+		let is_kept = keep::is_kept(
+			&profile.keep_rules,
+			&profile.branch,
+			&mods_path.join(&to_delete),
+		);
+		state.to_delete_names.insert(to_delete, !is_kept);
+	}
+	for to_delete_optional in to_delete_optionals {
+		state.to_delete_names.insert(to_delete_optional, false);
+	}
+	state.branch_info = Some(branch_info);
+
+	Ok(state)
+}
+
+/// Print what syncing the last used profile would do, without the gui and without changing anything
+/// Returns the process's exit code
+pub async fn dry_run(profiles_map: &ProfilesMap, preset: Option<&str>, json: bool) -> i32 {
+	let plan = match headless_state(profiles_map, preset).await {
+		Ok(state) => build(&state).await,
+		Err(err) => Err(err),
+	};
+
+	match plan {
+		Ok(plan) => {
+			println!(
+				"{}",
+				if json {
+					plan.to_json()
+				} else {
+					plan.to_markdown()
+				}
+			);
+			0
+		}
+		Err(err) => {
+			eprintln!("{}", err);
+			1
+		}
+	}
+}
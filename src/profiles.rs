@@ -8,12 +8,52 @@ use tokio::{
 	sync::{Mutex, OnceCell},
 };
 
+use crate::auth::AuthKind;
+use crate::cache::InstallMode;
+use crate::keep::{self, KeepRule};
+use crate::network::NetworkSettings;
+use crate::watcher::WatchSettings;
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Profile {
 	pub address: String,
+	/// Addresses tried in order when address is unreachable
+	#[serde(default)]
+	pub mirrors: Vec<String>,
 	pub branch: String,
 	pub mods_path: String,
-	pub keep_mods_in_branch: DashMap<String, Vec<String>>,
+	/// Exact file names not to delete, per branch
+	/// Only read to migrate them into keep_rules
+	#[serde(default, skip_serializing)]
+	keep_mods_in_branch: DashMap<String, Vec<String>>,
+	/// Mods in the to delete list matching any of these are kept
+	#[serde(default)]
+	pub keep_rules: Vec<KeepRule>,
+	/// Optional mods chosen for download, per branch
+	#[serde(default)]
+	pub selected_optionals: DashMap<String, Vec<String>>,
+	/// Optional mods the user has already seen, per branch, anything else is new
+	#[serde(default)]
+	pub known_optionals: DashMap<String, Vec<String>>,
+	#[serde(default)]
+	pub install_mode: InstallMode,
+	/// Download speed limit in bytes per second
+	#[serde(default)]
+	pub download_limit: Option<u64>,
+	/// Secret is stored in OS keyring (or encrypted file), not here
+	#[serde(default)]
+	pub auth: Option<AuthKind>,
+	#[serde(default)]
+	pub network: NetworkSettings,
+	/// Refuse branch manifests that aren't signed by trusted_key
+	#[serde(default)]
+	pub require_signature: bool,
+	/// Hex ed25519 public key of the hoster, trusted on first use
+	#[serde(default)]
+	pub trusted_key: Option<String>,
+	/// Polling the hoster for branch/mod changes
+	#[serde(default)]
+	pub watch: WatchSettings,
 }
 
 impl Profile {
@@ -24,9 +64,52 @@ impl Profile {
 	) -> Self {
 		Self {
 			address: address.into(),
+			mirrors: Vec::new(),
 			branch: branch.unwrap_or_default(),
 			mods_path: mods_path.into(),
 			keep_mods_in_branch: DashMap::new(),
+			keep_rules: Vec::new(),
+			selected_optionals: DashMap::new(),
+			known_optionals: DashMap::new(),
+			install_mode: InstallMode::default(),
+			download_limit: None,
+			auth: None,
+			network: NetworkSettings::default(),
+			require_signature: false,
+			trusted_key: None,
+			watch: WatchSettings::default(),
+		}
+	}
+
+	/// Turn keep lists of older versions into exact name rules scoped to their branch
+	fn migrate_keep_mods(&mut self) {
+		let mut branches: Vec<(String, Vec<String>)> =
+			self.keep_mods_in_branch.clone().into_iter().collect();
+		// INFO: dashmap's order is random, keep the rules stable between runs
+		branches.sort_by(|a, b| a.0.cmp(&b.0));
+
+		for (branch, names) in branches {
+			for name in names {
+				keep::set_kept(&mut self.keep_rules, &name, &branch, true);
+			}
+		}
+		self.keep_mods_in_branch.clear();
+	}
+
+	/// Remember (or forget) that an optional mod is wanted in branch
+	pub fn select_optional(&self, branch: &str, name: &str, selected: bool) {
+		let mut names = self
+			.selected_optionals
+			.entry(branch.to_string())
+			.or_default();
+		let index = names.iter().position(|v| v == name);
+
+		match (selected, index) {
+			(true, None) => names.push(name.to_string()),
+			(false, Some(index)) => {
+				names.swap_remove(index);
+			}
+			_ => {}
 		}
 	}
 }
@@ -141,6 +224,10 @@ pub async fn load_profiles() -> ProfilesMap {
 	let read_profiles: ProfilesMap =
 		serde_json::from_str(&buf).expect("Failed to serialize profiles file");
 
+	for mut profile in read_profiles.profiles.iter_mut() {
+		profile.migrate_keep_mods();
+	}
+
 	read_profiles
 }
 
@@ -172,3 +259,47 @@ pub async fn save_profiles(profiles_map: &ProfilesMap) {
 		.await
 		.expect("Failed to flush profiles file");
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const OLD_PROFILE: &str = r#"{
+		"address": "http://localhost",
+		"branch": "main",
+		"mods_path": "mods",
+		"keep_mods_in_branch": {"test": ["b.jar"], "main": ["a.jar", "b.jar"]}
+	}"#;
+
+	#[test]
+	fn migrates_keep_mods_into_rules() {
+		let mut profile: Profile = serde_json::from_str(OLD_PROFILE).unwrap();
+		profile.migrate_keep_mods();
+
+		assert_eq!(
+			profile.keep_rules,
+			vec![
+				KeepRule::exact("a.jar", "main"),
+				KeepRule::exact("b.jar", "main"),
+				KeepRule::exact("b.jar", "test"),
+			]
+		);
+		assert!(profile.keep_mods_in_branch.is_empty());
+	}
+
+	#[test]
+	fn migration_is_idempotent() {
+		let mut profile: Profile = serde_json::from_str(OLD_PROFILE).unwrap();
+		profile.migrate_keep_mods();
+		let rules = profile.keep_rules.clone();
+
+		profile.migrate_keep_mods();
+		assert_eq!(profile.keep_rules, rules);
+
+		// INFO: old list is never saved, but a profile loaded from it again mustn't duplicate rules
+		let mut reloaded: Profile =
+			serde_json::from_str(&serde_json::to_string(&profile).unwrap()).unwrap();
+		reloaded.migrate_keep_mods();
+		assert_eq!(reloaded.keep_rules, rules);
+	}
+}
@@ -0,0 +1,353 @@
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+use crate::{api, signing, tr};
+
+const BUNDLED_SIGNATURES: &str = include_str!("../signatures.json");
+const SIGNATURES_URL: &str =
+	"https://raw.githubusercontent.com/almafa64/minecraft-mod-syncer/main/signatures.json";
+/// Hex ed25519 signature of the file at SIGNATURES_URL
+const SIGNATURES_SIG_URL: &str =
+	"https://raw.githubusercontent.com/almafa64/minecraft-mod-syncer/main/signatures.json.sig";
+/// Hex public key the signatures are signed with, release builds set it
+const SIGNATURES_KEY: Option<&str> = option_env!("SIGNATURES_KEY");
+
+/// Jars in jars in jars... are rare, stop somewhere so zip bombs can't recurse forever
+const MAX_NESTED_DEPTH: usize = 4;
+/// Entries bigger than this aren't read into memory (32 MB)
+const MAX_ENTRY_SIZE: u64 = 32_000_000;
+/// Mods bundle their native libraries here (netty, lwjgl...), extensions there are fine
+const NATIVE_LIBS_DIR: &str = "meta-inf/native/";
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Pattern {
+	pub name: String,
+	/// Text to search for (e.g. url, package name in constant pool)
+	#[serde(default)]
+	pub text: Option<String>,
+	/// Hex bytes to search for
+	#[serde(default)]
+	pub hex: Option<String>,
+}
+
+/// Known-bad indicators, bundled with the program and updatable from the repository
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Signatures {
+	pub version: u32,
+	/// Hex sha256 of known-bad jars or class files
+	pub hashes: Vec<String>,
+	pub patterns: Vec<Pattern>,
+	/// Native executables/scripts have no reason to be in META-INF
+	pub suspicious_extensions: Vec<String>,
+}
+
+/// Signatures with patterns already converted to bytes
+struct CompiledSignatures {
+	version: u32,
+	hashes: Vec<String>,
+	patterns: Vec<(String, Vec<u8>)>,
+	suspicious_extensions: Vec<String>,
+}
+
+impl CompiledSignatures {
+	/// An update can't throw away every indicator of a list the current signatures have
+	fn keeps_lists_of(&self, current: &CompiledSignatures) -> bool {
+		(!self.hashes.is_empty() || current.hashes.is_empty())
+			&& (!self.patterns.is_empty() || current.patterns.is_empty())
+			&& (!self.suspicious_extensions.is_empty() || current.suspicious_extensions.is_empty())
+	}
+}
+
+impl From<Signatures> for CompiledSignatures {
+	fn from(signatures: Signatures) -> Self {
+		let patterns = signatures
+			.patterns
+			.into_iter()
+			.filter_map(|p| {
+				let bytes = match (p.text, p.hex) {
+					(Some(text), _) => text.into_bytes(),
+					(None, Some(hex)) => hex::decode(hex).ok()?,
+					(None, None) => return None,
+				};
+				(!bytes.is_empty()).then_some((p.name, bytes))
+			})
+			.collect();
+
+		Self {
+			version: signatures.version,
+			hashes: signatures
+				.hashes
+				.into_iter()
+				.map(|v| v.to_lowercase())
+				.collect(),
+			patterns,
+			suspicious_extensions: signatures
+				.suspicious_extensions
+				.into_iter()
+				.map(|v| v.to_lowercase())
+				.collect(),
+		}
+	}
+}
+
+fn signatures_path() -> PathBuf {
+	dirs::config_dir()
+		.expect("Couldnt access OS's default config dir")
+		.join("minecraft-mod-syncer")
+		.join("signatures.json")
+}
+
+/// Load the newest of the bundled and the downloaded signatures
+fn load_signatures() -> CompiledSignatures {
+	let bundled: CompiledSignatures = serde_json::from_str::<Signatures>(BUNDLED_SIGNATURES)
+		.expect("Bundled signatures are invalid")
+		.into();
+
+	let downloaded = std::fs::read_to_string(signatures_path())
+		.ok()
+		.and_then(|json| serde_json::from_str::<Signatures>(&json).ok())
+		.map(CompiledSignatures::from)
+		.filter(|v| v.version > bundled.version && v.keeps_lists_of(&bundled));
+
+	downloaded.unwrap_or(bundled)
+}
+
+static SIGNATURES: LazyLock<RwLock<Arc<CompiledSignatures>>> =
+	LazyLock::new(|| RwLock::new(Arc::new(load_signatures())));
+
+/// Download newest signatures from the repository
+/// Returns the version in use afterwards
+pub async fn update_signatures() -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
+	// INFO: a tampered list could hide malware, don't take one that can't be checked
+	let key = SIGNATURES_KEY
+		.and_then(signing::parse_key)
+		.ok_or_else(|| tr!("signatures.no_key"))?;
+
+	let client = api::get_client();
+	let json = client
+		.get(SIGNATURES_URL)
+		.send()
+		.await?
+		.error_for_status()?
+		.text()
+		.await?;
+	let signature = client
+		.get(SIGNATURES_SIG_URL)
+		.send()
+		.await?
+		.error_for_status()?
+		.text()
+		.await?;
+
+	if signing::verify(&key, json.as_bytes(), &signature).is_err() {
+		return Err(tr!("signatures.bad_signature").into());
+	}
+
+	// INFO: don't save something that can't be loaded
+	let signatures: CompiledSignatures = serde_json::from_str::<Signatures>(&json)?.into();
+	let current = SIGNATURES.read().unwrap().clone();
+
+	// INFO: even a signed file shouldn't switch the scanner off
+	if !signatures.keeps_lists_of(&current) {
+		return Err(tr!("signatures.emptied").into());
+	}
+
+	if signatures.version > current.version {
+		tokio::fs::write(signatures_path(), json).await?;
+		*SIGNATURES.write().unwrap() = Arc::new(signatures);
+	}
+
+	Ok(SIGNATURES.read().unwrap().version)
+}
+
+/// What scanning a jar found
+#[derive(Debug, Default)]
+pub struct ScanReport {
+	/// Known-bad hashes and patterns, the jar should be quarantined
+	pub malicious: Vec<String>,
+	/// Heuristics, legit mods can trigger these too so they are only reported
+	pub suspicious: Vec<String>,
+}
+
+impl ScanReport {
+	pub fn is_malicious(&self) -> bool {
+		!self.malicious.is_empty()
+	}
+
+	pub fn is_clean(&self) -> bool {
+		self.malicious.is_empty() && self.suspicious.is_empty()
+	}
+
+	/// Every finding, malicious ones first
+	pub fn findings(&self) -> Vec<String> {
+		self.malicious
+			.iter()
+			.chain(self.suspicious.iter())
+			.cloned()
+			.collect()
+	}
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+	hex::encode(Sha256::digest(bytes))
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+	haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+fn scan_bytes(
+	signatures: &CompiledSignatures,
+	entry_name: &str,
+	bytes: &[u8],
+	report: &mut ScanReport,
+) {
+	if signatures.hashes.contains(&sha256_hex(bytes)) {
+		report
+			.malicious
+			.push(format!("{}: {}", entry_name, tr!("scan.known_malicious")));
+	}
+
+	for (name, pattern) in signatures.patterns.iter() {
+		if contains(bytes, pattern) {
+			report.malicious.push(format!("{}: {}", entry_name, name));
+		}
+	}
+}
+
+fn scan_archive<R: Read + Seek>(
+	signatures: &CompiledSignatures,
+	archive: &mut ZipArchive<R>,
+	prefix: &str,
+	depth: usize,
+	report: &mut ScanReport,
+) -> zip::result::ZipResult<()> {
+	for i in 0..archive.len() {
+		let mut file = archive.by_index(i)?;
+		let name = format!("{}{}", prefix, file.name());
+		let lower_name = file.name().to_lowercase();
+
+		if lower_name.starts_with("meta-inf/") && !lower_name.starts_with(NATIVE_LIBS_DIR) {
+			let extension = lower_name.rsplit('.').next().unwrap_or_default();
+			if signatures
+				.suspicious_extensions
+				.iter()
+				.any(|v| v == extension)
+			{
+				report
+					.suspicious
+					.push(format!("{}: {}", name, tr!("scan.executable_in_meta_inf")));
+			}
+
+			// INFO: multi-release jars keep classes in META-INF/versions, anything else is odd
+			if extension == "class" && !lower_name.starts_with("meta-inf/versions/") {
+				report
+					.suspicious
+					.push(format!("{}: {}", name, tr!("scan.class_in_meta_inf")));
+			}
+		}
+
+		let is_class = lower_name.ends_with(".class");
+		let is_jar = lower_name.ends_with(".jar");
+
+		if !(is_class || is_jar) {
+			continue;
+		}
+
+		if file.size() > MAX_ENTRY_SIZE {
+			report
+				.suspicious
+				.push(format!("{}: {}", name, tr!("scan.not_scanned")));
+			continue;
+		}
+
+		let mut bytes = Vec::with_capacity(file.size() as usize);
+		file.read_to_end(&mut bytes)?;
+
+		scan_bytes(signatures, &name, &bytes, report);
+
+		if is_jar && depth < MAX_NESTED_DEPTH {
+			match ZipArchive::new(Cursor::new(bytes)) {
+				Ok(mut nested) => scan_archive(
+					signatures,
+					&mut nested,
+					&format!("{}!/", name),
+					depth + 1,
+					report,
+				)?,
+				Err(_) => {
+					report
+						.suspicious
+						.push(format!("{}: {}", name, tr!("scan.invalid_nested_jar")))
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Scan a jar for known malware indicators
+/// This blocks, run it with spawn_blocking
+pub fn scan_jar(path: &Path) -> std::io::Result<ScanReport> {
+	let signatures = SIGNATURES.read().unwrap().clone();
+	let mut report = ScanReport::default();
+
+	let bytes = std::fs::read(path)?;
+	scan_bytes(&signatures, "jar", &bytes, &mut report);
+
+	let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(std::io::Error::other)?;
+	scan_archive(&signatures, &mut archive, "", 0, &mut report).map_err(std::io::Error::other)?;
+
+	Ok(report)
+}
+
+/// Scan a jar without blocking the async runtime
+pub async fn scan_jar_async(path: &Path) -> std::io::Result<ScanReport> {
+	let path = path.to_path_buf();
+	tokio::task::spawn_blocking(move || scan_jar(&path))
+		.await
+		.map_err(std::io::Error::other)?
+}
+
+/// Move jar into the quarantine folder (with a report next to it) instead of deleting it
+/// Returns the new path
+pub async fn quarantine(
+	path: &Path,
+	file_name: &str,
+	findings: &[String],
+) -> std::io::Result<PathBuf> {
+	let dir = dirs::config_dir()
+		.expect("Couldnt access OS's default config dir")
+		.join("minecraft-mod-syncer")
+		.join("quarantine");
+	tokio::fs::create_dir_all(&dir).await?;
+
+	let time = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default();
+
+	// INFO: .quarantined so it can't be loaded by accident if copied back
+	let dest = dir.join(format!("{}-{}.quarantined", time, file_name));
+
+	if tokio::fs::rename(path, &dest).await.is_err() {
+		// INFO: rename fails across drives
+		tokio::fs::copy(path, &dest).await?;
+		tokio::fs::remove_file(path).await?;
+	}
+
+	tokio::fs::write(
+		dir.join(format!("{}-{}.txt", time, file_name)),
+		findings.join("\n"),
+	)
+	.await?;
+
+	Ok(dest)
+}
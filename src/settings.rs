@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+
+use fltk::enums;
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::theme;
+
+const SETTINGS_VERSION: u8 = 1;
+
+/// Languages selectable in preferences (code, name)
+pub const LANGUAGES: [(&str, &str); 3] = [("en", "English"), ("hu", "Magyar"), ("de", "Deutsch")];
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LabelAlign {
+	#[default]
+	Left,
+	Right,
+}
+
+impl LabelAlign {
+	pub fn to_align(self) -> enums::Align {
+		match self {
+			LabelAlign::Left => enums::Align::Left | enums::Align::Inside,
+			LabelAlign::Right => enums::Align::Right | enums::Align::Inside,
+		}
+	}
+}
+
+/// Program wide settings, profiles.json holds the per server ones
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+	version: u8,
+	pub label_align: LabelAlign,
+	/// Built-in or user colour scheme name, see theme::names
+	pub theme: String,
+	/// Code from LANGUAGES
+	pub language: String,
+	/// Save current profile when closing the window
+	pub auto_save_on_exit: bool,
+	/// Check github for newer version on start
+	pub check_updates: bool,
+	/// How many files are downloaded at the same time
+	pub download_concurrency: usize,
+	/// None for the default folder next to profiles.json
+	pub cache_dir: Option<String>,
+	/// Bytes, least recently used mods are evicted above this
+	pub cache_size_limit: u64,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			version: SETTINGS_VERSION,
+			label_align: LabelAlign::default(),
+			theme: String::from(theme::SYSTEM),
+			language: String::from("en"),
+			auto_save_on_exit: true,
+			check_updates: true,
+			download_concurrency: 4,
+			cache_dir: None,
+			cache_size_limit: cache::DEFAULT_SIZE_LIMIT,
+		}
+	}
+}
+
+impl Settings {
+	pub fn cache_dir(&self) -> PathBuf {
+		self.cache_dir
+			.as_deref()
+			.filter(|v| !v.is_empty())
+			.map(PathBuf::from)
+			.unwrap_or_else(cache::default_dir)
+	}
+}
+
+fn settings_path() -> PathBuf {
+	dirs::config_dir()
+		.expect("Couldnt access OS's default config dir")
+		.join("minecraft-mod-syncer")
+		.join("settings.json")
+}
+
+/// Read in settings file, defaults if it doesn't exist or is broken
+fn load() -> Settings {
+	let json = match std::fs::read_to_string(settings_path()) {
+		Ok(json) => json,
+		Err(_) => return Settings::default(),
+	};
+
+	let mut settings: Settings = serde_json::from_str(&json).unwrap_or_else(|err| {
+		println!("Settings file is corrupt, using defaults: {}", err);
+		Settings::default()
+	});
+
+	// INFO: nothing to migrate yet, newer fields are defaulted by serde
+	settings.version = SETTINGS_VERSION;
+
+	settings
+}
+
+static SETTINGS: LazyLock<RwLock<Settings>> = LazyLock::new(|| RwLock::new(load()));
+
+pub fn get() -> Settings {
+	SETTINGS.read().unwrap().clone()
+}
+
+/// Use settings from now on and write them to the settings file
+pub async fn set(settings: Settings) {
+	let json = serde_json::to_string(&settings).expect("Failed to convert settings to json");
+	*SETTINGS.write().unwrap() = settings;
+
+	if let Err(err) = tokio::fs::write(settings_path(), json).await {
+		println!("Failed to write settings file: {}", err);
+	}
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::tr;
+
+/// Header where the hoster sends the hex ed25519 signature of the branch manifest (response body)
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// What a profile expects from the hoster's manifests
+#[derive(Debug, Clone, Default)]
+pub struct SignaturePolicy {
+	/// Refuse manifests without valid signature
+	pub required: bool,
+	/// Hex public key the user trusted
+	pub trusted_key: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+	Request(reqwest::Error),
+	Json(serde_json::Error),
+	/// Local folder source couldn't be read
+	Io(std::io::Error),
+	/// Signature is required but server didn't send one
+	Unsigned,
+	BadSignature,
+	/// Manifest is signed, but the user hasn't trusted or declined its key yet, contains the server's hex key
+	UntrustedKey(String),
+}
+
+impl fmt::Display for ManifestError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ManifestError::Request(err) => write!(f, "{}", err),
+			ManifestError::Json(err) => {
+				write!(f, "{}", tr!("manifest.unknown_format", error = err))
+			}
+			ManifestError::Io(err) => write!(f, "{}", tr!("manifest.local_source", error = err)),
+			ManifestError::Unsigned => write!(f, "{}", tr!("manifest.unsigned")),
+			ManifestError::BadSignature => write!(f, "{}", tr!("manifest.bad_signature")),
+			ManifestError::UntrustedKey(_) => write!(f, "{}", tr!("manifest.untrusted_key")),
+		}
+	}
+}
+
+impl From<reqwest::Error> for ManifestError {
+	fn from(err: reqwest::Error) -> Self {
+		ManifestError::Request(err)
+	}
+}
+
+impl From<std::io::Error> for ManifestError {
+	fn from(err: std::io::Error) -> Self {
+		ManifestError::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for ManifestError {
+	fn from(err: serde_json::Error) -> Self {
+		ManifestError::Json(err)
+	}
+}
+
+pub fn parse_key(key_hex: &str) -> Option<VerifyingKey> {
+	let bytes: [u8; 32] = hex::decode(key_hex.trim()).ok()?.try_into().ok()?;
+	VerifyingKey::from_bytes(&bytes).ok()
+}
+
+/// Human comparable fingerprint of a hex key (sha256 of the key bytes, colon separated)
+pub fn fingerprint(key_hex: &str) -> String {
+	let digest = Sha256::digest(hex::decode(key_hex.trim()).unwrap_or_default());
+
+	digest
+		.iter()
+		.map(|b| format!("{:02X}", b))
+		.collect::<Vec<String>>()
+		.join(":")
+}
+
+/// Verify manifest body with the hex signature from SIGNATURE_HEADER
+pub fn verify(key: &VerifyingKey, body: &[u8], signature_hex: &str) -> Result<(), ManifestError> {
+	let bytes: [u8; 64] = hex::decode(signature_hex.trim())
+		.ok()
+		.and_then(|v| v.try_into().ok())
+		.ok_or(ManifestError::BadSignature)?;
+
+	key.verify_strict(body, &Signature::from_bytes(&bytes))
+		.map_err(|_| ManifestError::BadSignature)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// INFO: test 2 of RFC 8032, the message is the single byte 0x72
+	const KEY: &str = "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
+	const BODY: &[u8] = b"r";
+	const SIGNATURE: &str = "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00";
+	/// Test 1 of RFC 8032
+	const OTHER_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+	#[test]
+	fn valid_signature() {
+		let key = parse_key(KEY).unwrap();
+		assert!(verify(&key, BODY, SIGNATURE).is_ok());
+		// INFO: headers can come with whitespace around them
+		assert!(verify(&key, BODY, &format!(" {}\n", SIGNATURE)).is_ok());
+	}
+
+	#[test]
+	fn tampered_body() {
+		let key = parse_key(KEY).unwrap();
+		assert!(matches!(
+			verify(&key, b"s", SIGNATURE),
+			Err(ManifestError::BadSignature)
+		));
+	}
+
+	#[test]
+	fn other_key() {
+		let key = parse_key(OTHER_KEY).unwrap();
+		assert!(matches!(
+			verify(&key, BODY, SIGNATURE),
+			Err(ManifestError::BadSignature)
+		));
+	}
+
+	#[test]
+	fn malformed_signature() {
+		let key = parse_key(KEY).unwrap();
+		let not_hex = SIGNATURE.replace('a', "z");
+		let too_short = &SIGNATURE[2..];
+
+		for signature in [not_hex.as_str(), too_short, ""] {
+			assert!(matches!(
+				verify(&key, BODY, signature),
+				Err(ManifestError::BadSignature)
+			));
+		}
+	}
+
+	#[test]
+	fn wrong_key_length() {
+		assert!(parse_key(&KEY[2..]).is_none());
+		assert!(parse_key(&format!("{}00", KEY)).is_none());
+		assert!(parse_key("not a key").is_none());
+		assert!(parse_key(&format!(" {}\n", KEY)).is_some());
+	}
+
+	#[test]
+	fn fingerprint_is_sha256_of_key() {
+		let value = fingerprint(KEY);
+		assert_eq!(value.len(), 32 * 3 - 1);
+		assert_eq!(value, fingerprint(&format!(" {}\n", KEY)));
+		assert_ne!(value, fingerprint(OTHER_KEY));
+	}
+}
@@ -2,17 +2,22 @@ use std::collections::{HashMap, HashSet};
 use std::io::{Read, Result};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
 
 use fltk::app;
 use futures_util::StreamExt;
+use reqwest::StatusCode;
 use tokio::fs::File;
-use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
 use tokio::sync::{Mutex, RwLock, mpsc};
 use tokio::time::{Duration, Instant};
 use zip::ZipArchive;
 
 use crate::api::{self, Mod};
-use crate::{AppState, Events};
+use crate::cache::{self, InstallMode};
+use crate::{AppState, Events, tr};
+use crate::{jar, planner, scanner, settings, throttle};
 
 pub type ModNames = Vec<String>;
 pub type Mods = Vec<Mod>;
@@ -78,17 +83,78 @@ pub fn get_local_mods(mod_dir_path: &Path) -> Result<ModNames> {
 	Ok(mod_names)
 }
 
-/// Get all mods that are in remote_mods but not in local_mods
-pub fn get_mods_to_download(remote_mods: &Mods, local_mods: &ModNames) -> Mods {
+/// Size and modification date (unix seconds) of a file, zeros if it can't be read
+pub fn size_and_date(path: &Path) -> (u64, f64) {
+	let metadata = match path.metadata() {
+		Ok(metadata) => metadata,
+		Err(_) => return (0, 0.0),
+	};
+
+	let date = metadata
+		.modified()
+		.ok()
+		.and_then(|v| v.duration_since(UNIX_EPOCH).ok())
+		.map(|v| v.as_secs_f64())
+		.unwrap_or_default();
+
+	(metadata.len(), date)
+}
+
+/// Returns true if local mod differs from the server's one
+/// Size differs or it's older than the server's (1s slack for filesystems with coarse timestamps)
+pub fn is_outdated(path: &Path, mcmod: &Mod) -> bool {
+	let metadata = match path.metadata() {
+		Ok(metadata) => metadata,
+		Err(_) => return true,
+	};
+
+	if metadata.len() != mcmod.size {
+		return true;
+	}
+
+	let modified = metadata
+		.modified()
+		.ok()
+		.and_then(|v| v.duration_since(UNIX_EPOCH).ok())
+		.map(|v| v.as_secs_f64());
+
+	modified.is_some_and(|v| v < mcmod.mod_date - 1.0)
+}
+
+/// Get all mods that are in remote_mods but not in local_mods, or are outdated locally
+pub fn get_mods_to_download(remote_mods: &Mods, local_mods: &ModNames, mods_path: &Path) -> Mods {
 	let local_mod_names: HashSet<&String> = HashSet::from_iter(local_mods);
 
 	remote_mods
 		.iter()
-		.filter(|e| !local_mod_names.contains(&e.name))
+		.filter(|e| !local_mod_names.contains(&e.name) || is_outdated(&mods_path.join(&e.name), e))
 		.cloned()
 		.collect()
 }
 
+/// Set file's modification time to when the server last changed the mod
+/// So later runs can tell if the mod changed and the folder can be sorted by it
+async fn apply_mod_date(path: &Path, mod_date: f64) {
+	if !mod_date.is_finite() || mod_date <= 0.0 {
+		return;
+	}
+
+	let path = path.to_path_buf();
+	let time = UNIX_EPOCH + Duration::from_secs_f64(mod_date);
+
+	let res = tokio::task::spawn_blocking(move || {
+		std::fs::File::options()
+			.write(true)
+			.open(&path)?
+			.set_modified(time)
+	})
+	.await;
+
+	if let Ok(Err(err)) = res {
+		println!("Failed to set modification time: {}", err);
+	}
+}
+
 /// Get all mod names that are in local_mods but not in remote_mods
 /// And get all installed optional mod names
 // TODO: return 1 vec with Mod struct
@@ -112,6 +178,393 @@ pub fn get_mods_to_delete(remote_mods: &Mods, local_mods: &ModNames) -> (ModName
 	)
 }
 
+/// How many times an interrupted zip download is resumed before giving up
+const MAX_RESUME_RETRIES: usize = 3;
+/// How many times a broken (e.g. truncated) mod is downloaded again before giving up
+const MAX_VALIDATION_RETRIES: usize = 2;
+
+/// Measures download speed over all running downloads and reports it to the gui
+struct SpeedMeter {
+	prev_time: Instant,
+	check_ms: Duration,
+	size_under_time: usize,
+	prev_bps: f64,
+}
+
+impl SpeedMeter {
+	fn new(check_ms: Duration) -> Self {
+		Self {
+			prev_time: Instant::now(),
+			check_ms,
+			size_under_time: 0,
+			prev_bps: 0.0,
+		}
+	}
+
+	fn add(&mut self, chunk_size: usize, fltk_tx: app::Sender<Events>) {
+		self.size_under_time += chunk_size;
+
+		if self.prev_time.elapsed() >= self.check_ms {
+			let secs = self.prev_time.elapsed().as_secs_f64();
+			let bps = self.size_under_time as f64 / secs;
+
+			// spare some events from firing if net is stable
+			if bps != self.prev_bps {
+				fltk_tx.send(Events::DownloadSpeedMeter { bytes_per_s: bps });
+				planner::record_throughput(bps);
+				self.prev_bps = bps;
+			}
+
+			self.prev_time = Instant::now();
+			self.size_under_time = 0;
+		}
+	}
+}
+
+/// Install mod from the shared cache instead of the network and report it as downloaded
+/// Cached mod is checked like a download, anything can write into the cache folder
+/// Returns false if mod isn't cached or failed the checks (it's dropped from the cache then)
+async fn install_from_cache(
+	fltk_tx: app::Sender<Events>,
+	mcmod: &Mod,
+	dest: &Path,
+	mode: InstallMode,
+	count: usize,
+	total_file_count: usize,
+) -> bool {
+	let cached = match cache::get(mcmod).await {
+		Some(cached) => cached,
+		None => return false,
+	};
+
+	if let Err(err) = jar::validate_async(&cached, mcmod.size, mcmod.hash.as_deref()).await {
+		println!("cached {} is broken, downloading it: {}", mcmod.name, err);
+		cache::remove(mcmod).await;
+		return false;
+	}
+
+	match scanner::scan_jar_async(&cached).await {
+		Ok(report) if !report.is_malicious() => {}
+		res => {
+			println!(
+				"cached {} didn't pass the scan, downloading it: {:?}",
+				mcmod.name, res
+			);
+			cache::remove(mcmod).await;
+			return false;
+		}
+	}
+
+	if let Err(err) = cache::install(&cached, dest, mode).await {
+		println!("Failed to install '{}' from cache: {}", mcmod.name, err);
+		return false;
+	}
+
+	apply_mod_date(dest, mcmod.mod_date).await;
+
+	fltk_tx.send(Events::DownloadNewFile {
+		title: mcmod.name.clone(),
+		size: mcmod.size,
+		count,
+		total_file_count,
+	});
+	fltk_tx.send(Events::DownloadProgess {
+		count,
+		downloaded_chunk: mcmod.size as usize,
+	});
+
+	true
+}
+
+/// Where a mod is written while downloading, it only gets its real name after it passed the checks
+fn part_path(dest: &Path) -> PathBuf {
+	let mut name = dest.file_name().unwrap_or_default().to_os_string();
+	name.push(".part");
+	dest.with_file_name(name)
+}
+
+/// Scan downloaded mod for malware, then move it to its place
+/// Mods matching known signatures are quarantined instead, heuristic findings are only reported
+/// Returns true if mod got installed
+async fn finish_install(
+	fltk_tx: app::Sender<Events>,
+	part: &Path,
+	dest: &Path,
+	mcmod: &Mod,
+) -> bool {
+	let name = &mcmod.name;
+	let report = match scanner::scan_jar_async(part).await {
+		Ok(report) => report,
+		Err(err) => {
+			fltk_tx.send(Events::Alert(tr!(
+				"alert.scan_failed",
+				name = name,
+				error = err
+			)));
+			let _ = tokio::fs::remove_file(part).await;
+			return false;
+		}
+	};
+
+	if report.is_malicious() {
+		let findings = report.findings();
+		println!("malware indicators in {}: {:?}", name, findings);
+
+		match scanner::quarantine(part, name, &findings).await {
+			Ok(quarantined) => fltk_tx.send(Events::Alert(tr!(
+				"alert.quarantined",
+				name = name,
+				path = quarantined.display(),
+				findings = findings.join("\n")
+			))),
+			Err(err) => {
+				fltk_tx.send(Events::Alert(tr!(
+					"alert.quarantine_failed",
+					name = name,
+					error = err
+				)));
+				let _ = tokio::fs::remove_file(part).await;
+			}
+		}
+
+		return false;
+	}
+
+	if !report.suspicious.is_empty() {
+		println!("suspicious entries in {}: {:?}", name, report.suspicious);
+		fltk_tx.send(Events::Message(tr!(
+			"message.suspicious_mod",
+			name = name,
+			findings = report.suspicious.join("\n")
+		)));
+	}
+
+	// INFO: before the rename, so a partly installed mod is never newer than the server's
+	apply_mod_date(part, mcmod.mod_date).await;
+
+	if let Err(err) = tokio::fs::rename(part, dest).await {
+		fltk_tx.send(Events::Alert(tr!(
+			"alert.install_failed",
+			name = name,
+			error = err
+		)));
+		let _ = tokio::fs::remove_file(part).await;
+		return false;
+	}
+
+	true
+}
+
+/// Returns true if download was canceled by the user
+/// Concurrent downloads share one receiver, so whoever gets the signal sets the flag for everyone
+fn should_stop(progress_stop_rx: &Mutex<mpsc::Receiver<bool>>, stopped: &AtomicBool) -> bool {
+	if let Ok(mut rx) = progress_stop_rx.try_lock()
+		&& let Ok(true) = rx.try_recv()
+	{
+		stopped.store(true, Ordering::Relaxed);
+	}
+
+	stopped.load(Ordering::Relaxed)
+}
+
+/// Shared state of concurrently running file downloads
+struct FileDownloader<'a> {
+	fltk_tx: app::Sender<Events>,
+	progress_stop_rx: &'a Mutex<mpsc::Receiver<bool>>,
+	stopped: &'a AtomicBool,
+	speed_meter: &'a std::sync::Mutex<SpeedMeter>,
+	download_address: &'a str,
+	branch_name: &'a str,
+	install_mode: InstallMode,
+	total_count: usize,
+}
+
+impl FileDownloader<'_> {
+	/// Download mod into part file
+	/// Returns false if download failed or was canceled (part file is removed then)
+	async fn download_to(&self, mcmod: &Mod, part: &Path, count: usize) -> bool {
+		if let Some(src) = api::local_mod_path(self.download_address, self.branch_name, &mcmod.name)
+		{
+			return self.copy_local(&src, mcmod, part, count).await;
+		}
+
+		let res = api::request_mod(self.download_address, self.branch_name, &mcmod.name).await;
+		let res = match res {
+			Ok(res) => res,
+			Err(err) => {
+				println!("error in download: {}", err);
+				return false;
+			}
+		};
+
+		if res.status() == StatusCode::UNAUTHORIZED {
+			// INFO: every other mod would fail the same way, stop them and ask for a login once
+			if !self.stopped.swap(true, Ordering::Relaxed) {
+				self.fltk_tx.send(Events::LoginRequired);
+			}
+			return false;
+		}
+
+		if !res.status().is_success() {
+			println!("http status: {}", res.status().as_u16());
+			return false;
+		}
+
+		// TODO: change server zipping code
+		let file_size = res.content_length().unwrap_or(u64::MAX);
+
+		let file = File::create(part).await.unwrap();
+		let mut file_out = BufWriter::new(file);
+
+		// TODO: move total_count out of here
+		self.fltk_tx.send(Events::DownloadNewFile {
+			title: mcmod.name.clone(),
+			size: file_size,
+			count,
+			total_file_count: self.total_count,
+		});
+
+		let mut stream = res.bytes_stream();
+
+		while let Some(chunk) = stream.next().await {
+			if should_stop(self.progress_stop_rx, self.stopped) {
+				break;
+			}
+
+			// TODO: try again chunk instead
+			// INFO: delete file to indicate failure
+			let chunk = match chunk {
+				Ok(chunk) => chunk,
+				Err(e) => {
+					self.fltk_tx.send(Events::Alert(tr!(
+						"alert.download_error",
+						error = format!("{:?}", e)
+					)));
+					file_out.shutdown().await.unwrap();
+					tokio::fs::remove_file(part).await.unwrap();
+					return false;
+				}
+			};
+
+			let chunk_size = chunk.len();
+			throttle::acquire(chunk_size).await;
+			self.speed_meter
+				.lock()
+				.unwrap()
+				.add(chunk_size, self.fltk_tx);
+
+			file_out.write_all(&chunk).await.unwrap();
+
+			self.fltk_tx.send(Events::DownloadProgess {
+				count,
+				downloaded_chunk: chunk_size,
+			});
+		}
+
+		file_out.shutdown().await.unwrap();
+
+		if self.stopped.load(Ordering::Relaxed) {
+			tokio::fs::remove_file(part).await.unwrap();
+			return false;
+		}
+
+		true
+	}
+
+	/// Copy mod from a local folder source into part file
+	/// Returns false if copy failed or was canceled (part file is removed then)
+	async fn copy_local(&self, src: &Path, mcmod: &Mod, part: &Path, count: usize) -> bool {
+		let mut file_in = match File::open(src).await {
+			Ok(file) => file,
+			Err(err) => {
+				println!("error in copy: {}", err);
+				return false;
+			}
+		};
+
+		let file = File::create(part).await.unwrap();
+		let mut file_out = BufWriter::new(file);
+
+		self.fltk_tx.send(Events::DownloadNewFile {
+			title: mcmod.name.clone(),
+			size: mcmod.size,
+			count,
+			total_file_count: self.total_count,
+		});
+
+		let mut buf = vec![0u8; 64 * 1024];
+		let mut failed = false;
+
+		loop {
+			if should_stop(self.progress_stop_rx, self.stopped) {
+				failed = true;
+				break;
+			}
+
+			let size = match file_in.read(&mut buf).await {
+				Ok(0) => break,
+				Ok(size) => size,
+				Err(err) => {
+					self.fltk_tx
+						.send(Events::Alert(tr!("alert.copy_error", error = err)));
+					failed = true;
+					break;
+				}
+			};
+
+			self.speed_meter.lock().unwrap().add(size, self.fltk_tx);
+			file_out.write_all(&buf[..size]).await.unwrap();
+
+			self.fltk_tx.send(Events::DownloadProgess {
+				count,
+				downloaded_chunk: size,
+			});
+		}
+
+		file_out.shutdown().await.unwrap();
+
+		if failed {
+			tokio::fs::remove_file(part).await.unwrap();
+		}
+
+		!failed
+	}
+
+	/// Download mod, download it again if it's broken, then install it
+	async fn download_and_install(&self, mcmod: &Mod, dest: &Path, count: usize) {
+		let part = part_path(dest);
+		let mut retries = 0;
+
+		loop {
+			if !self.download_to(mcmod, &part, count).await {
+				return;
+			}
+
+			match jar::validate_async(&part, mcmod.size, mcmod.hash.as_deref()).await {
+				Ok(()) => break,
+				Err(err) if retries < MAX_VALIDATION_RETRIES => {
+					retries += 1;
+					println!("{} is broken, downloading again: {}", mcmod.name, err);
+				}
+				Err(err) => {
+					self.fltk_tx.send(Events::Alert(tr!(
+						"alert.still_broken",
+						count = retries + 1,
+						name = mcmod.name,
+						error = err
+					)));
+					let _ = tokio::fs::remove_file(&part).await;
+					return;
+				}
+			}
+		}
+
+		if finish_install(self.fltk_tx, &part, dest, mcmod).await {
+			cache::insert(mcmod, dest, self.install_mode).await;
+		}
+	}
+}
+
 pub async fn download_files(
 	fltk_tx: app::Sender<Events>,
 	progress_stop_rx: Arc<Mutex<mpsc::Receiver<bool>>>,
@@ -125,113 +578,63 @@ pub async fn download_files(
 	let branch_info = app_state_locked.branch_info.as_ref().unwrap();
 	let mcmods = &branch_info.mods;
 	let mods_pathbuf = app_state_locked.mods_path.as_ref().unwrap();
+	let install_mode = app_state_locked.install_mode;
 
 	let to_downloads: HashSet<&String> = app_state_locked
 		.to_download_names
 		.iter()
-		.filter_map(|e| e.1.then(|| e.0))
+		.filter_map(|e| e.1.then_some(e.0))
 		.collect();
 
 	let mcmods: Vec<&api::Mod> = mcmods
-		.into_iter()
+		.iter()
 		.filter(|x| to_downloads.contains(&x.name))
 		.collect();
 
-	let mut prev_time = Instant::now();
-	let check_ms = Duration::from_millis(500);
-	let mut size_under_time = 0;
-	let mut prev_bps = 0.0;
-
-	for (i, mcmod) in mcmods.iter().enumerate() {
-		let res = api::request_mod(&download_address, &branch_name, &mcmod.name).await;
-		match res {
-			Ok(res) => {
-				if !res.status().is_success() {
-					println!("http status: {}", res.status().as_u16());
+	let stopped = AtomicBool::new(false);
+	let speed_meter = std::sync::Mutex::new(SpeedMeter::new(Duration::from_millis(500)));
+
+	let downloader = FileDownloader {
+		fltk_tx,
+		progress_stop_rx: &progress_stop_rx,
+		stopped: &stopped,
+		speed_meter: &speed_meter,
+		download_address,
+		branch_name,
+		install_mode,
+		total_count,
+	};
+
+	futures_util::stream::iter(mcmods.iter().enumerate())
+		.for_each_concurrent(settings::get().download_concurrency.max(1), |(i, mcmod)| {
+			let downloader = &downloader;
+			let stopped = &stopped;
+
+			async move {
+				if stopped.load(Ordering::Relaxed) {
 					return;
 				}
 
-				// TODO: change server zipping code
-				let file_size = res.content_length().unwrap_or(u64::max_value());
-
 				let path = mods_pathbuf.join(&mcmod.name);
-				let file = File::create(&path).await.unwrap();
-				let mut file_out = BufWriter::new(file);
-
-				// TODO: move total_count out of here
-				fltk_tx.send(Events::DownloadNewFile {
-					title: mcmod.name.clone(),
-					size: file_size,
-					count: i,
-					total_file_count: total_count,
-				});
-
-				let mut stream = res.bytes_stream();
-				let mut stopped = false;
-
-				let mut progress_stop_rx = progress_stop_rx.lock().await;
-				while let Some(chunk) = stream.next().await {
-					if let Ok(true) = progress_stop_rx.try_recv() {
-						stopped = true;
-						break;
-					}
-
-					// TODO: try again chunk instead
-					// INFO: delete file to indicate failure
-					if chunk.is_err() {
-						let e = chunk.unwrap_err();
-						fltk_tx.send(Events::Alert(format!("Error during downloading: {:?}", e)));
-						file_out.shutdown().await.unwrap();
-						tokio::fs::remove_file(&path).await.unwrap();
-						break;
-					}
-
-					let chunk = chunk.unwrap();
-					let chunk_size = chunk.len();
-					size_under_time += chunk_size;
-
-					if prev_time.elapsed() >= check_ms {
-						let secs = prev_time.elapsed().as_secs_f64();
-						let bps = size_under_time as f64 / secs;
-
-						// spare some events from firing if net is stable
-						if bps != prev_bps {
-							fltk_tx.send(Events::DownloadSpeedMeter { bytes_per_s: bps });
-							prev_bps = bps;
-						}
-
-						prev_time = Instant::now();
-						size_under_time = 0;
-					}
-
-					file_out.write_all(&chunk).await.unwrap();
-
-					fltk_tx.send(Events::DownloadProgess {
-						downloaded_chunk: chunk_size,
-					});
-				}
-
-				file_out.shutdown().await.unwrap();
-
-				if stopped {
-					fltk_tx.send(Events::DownloadStop);
-					tokio::fs::remove_file(&path).await.unwrap();
+				if install_from_cache(fltk_tx, mcmod, &path, install_mode, i, total_count).await {
 					return;
 				}
+
+				downloader.download_and_install(mcmod, &path, i).await;
 			}
-			Err(err) => {
-				println!("error in download: {}", err);
-			}
-		}
-	}
+		})
+		.await;
 
 	fltk_tx.send(Events::DownloadStop);
 }
 
+/// Download the branch's zip then extract the selected mods from it
+/// If resumable is set, an interrupted download continues where it stopped
 pub async fn download_zip(
 	fltk_tx: app::Sender<Events>,
 	progress_stop_rx: Arc<Mutex<mpsc::Receiver<bool>>>,
 	app_state: Arc<RwLock<AppState>>,
+	resumable: bool,
 ) {
 	let app_state_locked = app_state.read().await;
 
@@ -241,13 +644,19 @@ pub async fn download_zip(
 	let res = api::request_mod_zip(&download_address, &branch_name).await;
 	match res {
 		Ok(res) => {
+			if res.status() == StatusCode::UNAUTHORIZED {
+				fltk_tx.send(Events::DownloadStop);
+				fltk_tx.send(Events::LoginRequired);
+				return;
+			}
+
 			if !res.status().is_success() {
 				println!("http status: {}", res.status().as_u16());
 				return;
 			}
 
 			// TODO: change server zipping code
-			let file_size = res.content_length().unwrap_or(u64::max_value());
+			let file_size = res.content_length().unwrap_or(u64::MAX);
 
 			let file_name = format!("{}.zip", &branch_name);
 			let path = Path::new(".").join(&file_name);
@@ -264,11 +673,10 @@ pub async fn download_zip(
 
 			let mut stream = res.bytes_stream();
 			let mut stopped = false;
+			let mut downloaded: u64 = 0;
+			let mut retries = 0;
 
-			let mut prev_time = Instant::now();
-			let check_ms = Duration::from_millis(500);
-			let mut size_under_time = 0;
-			let mut prev_bps = 0.0;
+			let mut speed_meter = SpeedMeter::new(Duration::from_millis(500));
 
 			let mut progress_stop_rx_locked = progress_stop_rx.lock().await;
 			while let Some(chunk) = stream.next().await {
@@ -277,35 +685,56 @@ pub async fn download_zip(
 					break;
 				}
 
-				// TODO: try again chunk instead
-				// INFO: delete file to indicate failure
-				if chunk.is_err() {
-					let e = chunk.unwrap_err();
-					fltk_tx.send(Events::Alert(format!("Error during downloading: {:?}", e)));
-					stopped = true;
-					break;
-				}
-
-				let chunk = chunk.unwrap();
-				let chunk_size = chunk.len();
-				size_under_time += chunk_size;
-
-				if prev_time.elapsed() >= check_ms {
-					let secs = prev_time.elapsed().as_secs_f64();
-					let bps = size_under_time as f64 / secs;
-
-					if bps != prev_bps {
-						fltk_tx.send(Events::DownloadSpeedMeter { bytes_per_s: bps });
-						prev_bps = bps;
+				let chunk = match chunk {
+					Ok(chunk) => chunk,
+					Err(e) if resumable && retries < MAX_RESUME_RETRIES => {
+						retries += 1;
+						println!(
+							"zip download interrupted at {} bytes, resuming: {}",
+							downloaded, e
+						);
+
+						match api::request_mod_zip_range(
+							&download_address,
+							&branch_name,
+							downloaded,
+						)
+						.await
+						{
+							Ok(res) if res.status() == StatusCode::PARTIAL_CONTENT => {
+								stream = res.bytes_stream();
+								continue;
+							}
+							_ => {
+								fltk_tx.send(Events::Alert(tr!(
+									"alert.download_error",
+									error = format!("{:?}", e)
+								)));
+								stopped = true;
+								break;
+							}
+						}
+					}
+					// INFO: delete file to indicate failure
+					Err(e) => {
+						fltk_tx.send(Events::Alert(tr!(
+							"alert.download_error",
+							error = format!("{:?}", e)
+						)));
+						stopped = true;
+						break;
 					}
+				};
 
-					prev_time = Instant::now();
-					size_under_time = 0;
-				}
+				let chunk_size = chunk.len();
+				downloaded += chunk_size as u64;
+				throttle::acquire(chunk_size).await;
+				speed_meter.add(chunk_size, fltk_tx);
 
 				file_out.write_all(&chunk).await.unwrap();
 
 				fltk_tx.send(Events::DownloadProgess {
+					count: 1,
 					downloaded_chunk: chunk_size,
 				});
 			}
@@ -341,23 +770,41 @@ pub async fn unzip_mod_zip(
 	let branch_info = app_state_locked.branch_info.as_ref().unwrap();
 	let mcmods = &branch_info.mods;
 	let mods_pathbuf = app_state_locked.mods_path.as_ref().unwrap();
+	let install_mode = app_state_locked.install_mode;
 
 	let to_downloads: HashSet<&String> = app_state_locked
 		.to_download_names
 		.iter()
-		.filter_map(|e| e.1.then(|| e.0))
+		.filter_map(|e| e.1.then_some(e.0))
 		.collect();
 
-	let mcmods: Vec<&api::Mod> = mcmods
-		.into_iter()
+	let mut mcmods: HashMap<&String, &api::Mod> = mcmods
+		.iter()
 		.filter(|x| to_downloads.contains(&x.name))
+		.map(|x| (&x.name, x))
 		.collect();
 
-	let total_size = mcmods.iter().fold(0, |acc, x| acc + x.size);
+	let total_size = mcmods.values().fold(0, |acc, x| acc + x.size);
+
+	// INFO: mods the zip has no good copy of (changed since it was made, missing or broken in it),
+	// these are downloaded one by one afterwards
+	let mut broken: Vec<&Mod> = mcmods
+		.values()
+		.filter(|v| v.mod_date > branch_info.zip.mod_date)
+		.copied()
+		.collect();
+	mcmods.retain(|_, v| v.mod_date <= branch_info.zip.mod_date);
 
 	let zip_file = std::fs::File::open(zip_path).unwrap();
 	let zip_reader = std::io::BufReader::new(zip_file);
-	let mut archive = ZipArchive::new(zip_reader).unwrap();
+	let mut archive = match ZipArchive::new(zip_reader) {
+		Ok(archive) => archive,
+		Err(err) => {
+			fltk_tx.send(Events::Alert(tr!("alert.zip_broken", error = err)));
+			tokio::fs::remove_file(&zip_path).await.unwrap();
+			return;
+		}
+	};
 
 	fltk_tx.send(Events::ShowDownloadModal {
 		total_size: total_size,
@@ -376,15 +823,22 @@ pub async fn unzip_mod_zip(
 	for i in 0..file_count {
 		let mut file = archive.by_index(i).unwrap();
 
-		if !to_downloads.contains(&file.name().to_string()) {
-			continue;
-		}
+		// INFO: removed, so whatever is left at the end wasn't in the zip
+		let mcmod = match mcmods.remove(&file.name().to_string()) {
+			Some(mcmod) => mcmod,
+			None => continue,
+		};
 
 		let outpath = match file.enclosed_name() {
 			Some(path) => mods_pathbuf.join(path),
 			None => continue,
 		};
 
+		// INFO: skip decompressing mods that are already cached
+		if install_from_cache(fltk_tx, mcmod, &outpath, install_mode, i, file_count).await {
+			continue;
+		}
+
 		fltk_tx.send(Events::DownloadNewFile {
 			title: file.name().to_string(),
 			size: file.size(),
@@ -392,8 +846,10 @@ pub async fn unzip_mod_zip(
 			total_file_count: file_count,
 		});
 
-		let out_file = File::create(&outpath).await.unwrap();
+		let part = part_path(&outpath);
+		let out_file = File::create(&part).await.unwrap();
 		let mut out_buf = BufWriter::new(out_file);
+		let mut read_failed = false;
 
 		loop {
 			if let Ok(true) = progress_stop_rx_locked.try_recv() {
@@ -412,6 +868,7 @@ pub async fn unzip_mod_zip(
 
 					if prev_time.elapsed() > check_ms {
 						fltk_tx.send(Events::DownloadProgess {
+							count: i,
 							downloaded_chunk: size_since_update,
 						});
 
@@ -421,7 +878,7 @@ pub async fn unzip_mod_zip(
 				}
 				Err(err) => {
 					println!("failed to write out file from zip: {}", err);
-					stopped = true;
+					read_failed = true;
 					break;
 				}
 			}
@@ -430,10 +887,60 @@ pub async fn unzip_mod_zip(
 		out_buf.shutdown().await.unwrap();
 
 		if stopped {
-			tokio::fs::remove_file(&outpath).await.unwrap();
+			tokio::fs::remove_file(&part).await.unwrap();
 			break;
 		}
+
+		if read_failed {
+			tokio::fs::remove_file(&part).await.unwrap();
+			broken.push(mcmod);
+			continue;
+		}
+
+		if let Err(err) = jar::validate_async(&part, mcmod.size, mcmod.hash.as_deref()).await {
+			println!("{} is broken in zip: {}", mcmod.name, err);
+			tokio::fs::remove_file(&part).await.unwrap();
+			broken.push(mcmod);
+			continue;
+		}
+
+		if finish_install(fltk_tx, &part, &outpath, mcmod).await {
+			cache::insert(mcmod, &outpath, install_mode).await;
+		}
 	}
 
 	tokio::fs::remove_file(&zip_path).await.unwrap();
+
+	broken.extend(mcmods.into_values());
+
+	if stopped || broken.is_empty() {
+		return;
+	}
+
+	// INFO: downloader locks progress_stop_rx too
+	drop(progress_stop_rx_locked);
+
+	let stopped = AtomicBool::new(false);
+	let speed_meter = std::sync::Mutex::new(SpeedMeter::new(Duration::from_millis(500)));
+
+	let downloader = FileDownloader {
+		fltk_tx,
+		progress_stop_rx: &progress_stop_rx,
+		stopped: &stopped,
+		speed_meter: &speed_meter,
+		download_address: app_state_locked.server_main_address.as_ref().unwrap(),
+		branch_name: app_state_locked.branch_name.as_ref().unwrap(),
+		install_mode,
+		total_count: broken.len(),
+	};
+
+	for (i, mcmod) in broken.into_iter().enumerate() {
+		if stopped.load(Ordering::Relaxed) {
+			break;
+		}
+
+		downloader
+			.download_and_install(mcmod, &mods_pathbuf.join(&mcmod.name), i)
+			.await;
+	}
 }
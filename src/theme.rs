@@ -0,0 +1,242 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{LazyLock, RwLock};
+
+use fltk::{app, enums::Color};
+use fltk_theme::ThemeType;
+use serde::{Deserialize, Serialize};
+
+pub const LIGHT: &str = "Light";
+pub const DARK: &str = "Dark";
+pub const HIGH_CONTRAST: &str = "High contrast";
+/// Light or dark, whichever the OS uses
+pub const SYSTEM: &str = "System";
+
+pub const BUILTIN_THEMES: [&str; 4] = [LIGHT, DARK, HIGH_CONTRAST, SYSTEM];
+
+/// Widget theme a colour scheme is drawn on top of
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Base {
+	#[default]
+	Light,
+	Dark,
+	HighContrast,
+}
+
+impl Base {
+	fn theme_type(self) -> ThemeType {
+		match self {
+			Base::Light => ThemeType::Classic,
+			Base::Dark => ThemeType::Dark,
+			Base::HighContrast => ThemeType::HighContrast,
+		}
+	}
+}
+
+/// Colours are "#rrggbb" strings so user files stay readable
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ColorScheme {
+	pub name: String,
+	pub base: Base,
+	/// Overrides of the base theme's window colours
+	pub background: Option<String>,
+	pub background2: Option<String>,
+	pub foreground: Option<String>,
+	pub selection: Option<String>,
+	/// Mod list entries
+	pub required: String,
+	pub optional: String,
+	pub kept: String,
+	pub to_delete: String,
+}
+
+impl Default for ColorScheme {
+	fn default() -> Self {
+		Self {
+			name: String::from(LIGHT),
+			base: Base::Light,
+			background: None,
+			background2: None,
+			foreground: None,
+			selection: None,
+			required: String::from("#000000"),
+			optional: String::from("#1a5fb4"),
+			kept: String::from("#26a269"),
+			to_delete: String::from("#c01c28"),
+		}
+	}
+}
+
+/// What a mod list entry stands for, decides its colour
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryColor {
+	Required,
+	Optional,
+	Kept,
+	ToDelete,
+}
+
+impl ColorScheme {
+	pub fn entry_color(&self, entry: EntryColor) -> Color {
+		let hex = match entry {
+			EntryColor::Required => &self.required,
+			EntryColor::Optional => &self.optional,
+			EntryColor::Kept => &self.kept,
+			EntryColor::ToDelete => &self.to_delete,
+		};
+
+		Color::from_hex_str(hex).unwrap_or(Color::Foreground)
+	}
+}
+
+fn builtin(name: &str) -> Option<ColorScheme> {
+	match name {
+		LIGHT => Some(ColorScheme::default()),
+		DARK => Some(ColorScheme {
+			name: String::from(DARK),
+			base: Base::Dark,
+			required: String::from("#ffffff"),
+			optional: String::from("#99c1f1"),
+			kept: String::from("#8ff0a4"),
+			to_delete: String::from("#f66151"),
+			..Default::default()
+		}),
+		HIGH_CONTRAST => Some(ColorScheme {
+			name: String::from(HIGH_CONTRAST),
+			base: Base::HighContrast,
+			required: String::from("#ffffff"),
+			optional: String::from("#00ffff"),
+			kept: String::from("#00ff00"),
+			to_delete: String::from("#ff4040"),
+			..Default::default()
+		}),
+		SYSTEM => builtin(if system_prefers_dark() { DARK } else { LIGHT }),
+		_ => None,
+	}
+}
+
+/// Best effort, light if the OS can't tell
+fn system_prefers_dark() -> bool {
+	let run = |cmd: &str, args: &[&str]| {
+		Command::new(cmd)
+			.args(args)
+			.output()
+			.ok()
+			.map(|v| String::from_utf8_lossy(&v.stdout).to_lowercase())
+			.unwrap_or_default()
+	};
+
+	if cfg!(target_os = "windows") {
+		run(
+			"reg",
+			&[
+				"query",
+				r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+				"/v",
+				"AppsUseLightTheme",
+			],
+		)
+		.contains("0x0")
+	} else if cfg!(target_os = "macos") {
+		run("defaults", &["read", "-g", "AppleInterfaceStyle"]).contains("dark")
+	} else {
+		std::env::var("GTK_THEME").is_ok_and(|v| v.to_lowercase().contains("dark"))
+			|| run(
+				"gsettings",
+				&["get", "org.gnome.desktop.interface", "color-scheme"],
+			)
+			.contains("dark")
+	}
+}
+
+/// User colour schemes are json files in here
+pub fn themes_dir() -> PathBuf {
+	dirs::config_dir()
+		.expect("Couldnt access OS's default config dir")
+		.join("minecraft-mod-syncer")
+		.join("themes")
+}
+
+/// Read in every scheme from themes_dir, broken files are skipped
+fn user_schemes() -> Vec<ColorScheme> {
+	let entries = match std::fs::read_dir(themes_dir()) {
+		Ok(entries) => entries,
+		Err(_) => return Vec::new(),
+	};
+
+	let mut schemes: Vec<ColorScheme> = entries
+		.flatten()
+		.map(|v| v.path())
+		.filter(|v| v.extension().is_some_and(|ext| ext == "json"))
+		.filter_map(|path| {
+			let json = std::fs::read_to_string(&path).ok()?;
+			match serde_json::from_str::<ColorScheme>(&json) {
+				Ok(mut scheme) => {
+					if scheme.name.is_empty() {
+						scheme.name = path.file_stem()?.to_string_lossy().to_string();
+					}
+					Some(scheme)
+				}
+				Err(err) => {
+					println!("Skipping broken theme {}: {}", path.display(), err);
+					None
+				}
+			}
+		})
+		.filter(|v| !BUILTIN_THEMES.contains(&v.name.as_str()))
+		.collect();
+
+	schemes.sort_by(|a, b| a.name.cmp(&b.name));
+	schemes
+}
+
+/// Built-in themes first, then the user's
+pub fn names() -> Vec<String> {
+	BUILTIN_THEMES
+		.iter()
+		.map(|v| v.to_string())
+		.chain(user_schemes().into_iter().map(|v| v.name))
+		.collect()
+}
+
+/// Scheme by name, Light if it doesn't exist (anymore)
+pub fn find(name: &str) -> ColorScheme {
+	builtin(name)
+		.or_else(|| user_schemes().into_iter().find(|v| v.name == name))
+		.unwrap_or_default()
+}
+
+static CURRENT: LazyLock<RwLock<ColorScheme>> =
+	LazyLock::new(|| RwLock::new(ColorScheme::default()));
+
+pub fn current() -> ColorScheme {
+	CURRENT.read().unwrap().clone()
+}
+
+/// Set widget theme and colours, lists have to be redrawn by caller
+pub fn apply(scheme: ColorScheme) {
+	fltk_theme::WidgetTheme::new(scheme.base.theme_type()).apply();
+
+	let rgb = |hex: &Option<String>| {
+		hex.as_deref()
+			.and_then(|v| Color::from_hex_str(v).ok())
+			.map(|v| v.to_rgb())
+	};
+
+	if let Some((r, g, b)) = rgb(&scheme.background) {
+		app::background(r, g, b);
+	}
+	if let Some((r, g, b)) = rgb(&scheme.background2) {
+		app::background2(r, g, b);
+	}
+	if let Some((r, g, b)) = rgb(&scheme.foreground) {
+		app::foreground(r, g, b);
+	}
+	if let Some((r, g, b)) = rgb(&scheme.selection) {
+		app::set_selection_color(r, g, b);
+	}
+
+	*CURRENT.write().unwrap() = scheme;
+}
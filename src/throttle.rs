@@ -0,0 +1,62 @@
+use std::sync::{LazyLock, Mutex};
+
+use tokio::time::{Duration, Instant};
+
+/// Token bucket shared by every running download, so the limit applies to their sum
+struct RateLimiter {
+	/// Bytes per second, None means unlimited
+	limit: Option<u64>,
+	/// Bytes that can be read without waiting, negative if over the limit
+	available: f64,
+	last_refill: Instant,
+}
+
+static LIMITER: LazyLock<Mutex<RateLimiter>> = LazyLock::new(|| {
+	Mutex::new(RateLimiter {
+		limit: None,
+		available: 0.0,
+		last_refill: Instant::now(),
+	})
+});
+
+/// Set download rate limit in bytes per second, None or 0 removes the limit
+/// Can be changed while downloads are running
+pub fn set_limit(bytes_per_s: Option<u64>) {
+	let mut limiter = LIMITER.lock().unwrap();
+
+	limiter.limit = bytes_per_s.filter(|v| *v > 0);
+	limiter.available = 0.0;
+	limiter.last_refill = Instant::now();
+}
+
+pub fn get_limit() -> Option<u64> {
+	LIMITER.lock().unwrap().limit
+}
+
+/// Wait until size bytes can be downloaded without going over the limit
+pub async fn acquire(size: usize) {
+	let wait = {
+		let mut limiter = LIMITER.lock().unwrap();
+
+		let limit = match limiter.limit {
+			Some(limit) => limit as f64,
+			None => return,
+		};
+
+		// INFO: refill at most 1 second worth of bytes, so idle time doesn't allow huge bursts
+		let now = Instant::now();
+		let elapsed = now.duration_since(limiter.last_refill).as_secs_f64();
+		limiter.available = (limiter.available + elapsed * limit).min(limit);
+		limiter.last_refill = now;
+
+		limiter.available -= size as f64;
+
+		if limiter.available >= 0.0 {
+			return;
+		}
+
+		Duration::from_secs_f64(-limiter.available / limit)
+	};
+
+	tokio::time::sleep(wait).await;
+}
@@ -13,3 +13,135 @@ pub fn readable_bps(bps: f64) -> String {
 
 	String::from("fast boi (>1000 TB/s)")
 }
+
+pub fn readable_size(size: f64) -> String {
+	const DIVIDER: f64 = 1000.0;
+	const MEASURES: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+	let mut size = size;
+
+	for measure in MEASURES.iter() {
+		if size < DIVIDER {
+			return format!("{:.2} {}", size, measure);
+		}
+
+		size /= DIVIDER;
+	}
+
+	String::from("huge (>1000 TB)")
+}
+
+/// "YYYY-MM-DD HH:MM UTC" from unix seconds, no timezone database needed
+pub fn readable_date(unix_secs: f64) -> String {
+	let secs = unix_secs as i64;
+	let days = secs.div_euclid(86_400);
+	let time = secs.rem_euclid(86_400);
+
+	// INFO: days to civil date, http://howardhinnant.github.io/date_algorithms.html
+	let z = days + 719_468;
+	let era = z.div_euclid(146_097);
+	let doe = z.rem_euclid(146_097);
+	let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+	format!(
+		"{:04}-{:02}-{:02} {:02}:{:02} UTC",
+		year,
+		month,
+		day,
+		time / 3600,
+		time % 3600 / 60
+	)
+}
+
+/// Shell style wildcard match, * is any run of characters, ? is one character
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().collect();
+	let text: Vec<char> = text.chars().collect();
+
+	let (mut p, mut t) = (0, 0);
+	// INFO: last * position and the text position it was tried at, for backtracking
+	let mut star: Option<(usize, usize)> = None;
+
+	while t < text.len() {
+		if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+			p += 1;
+			t += 1;
+		} else if p < pattern.len() && pattern[p] == '*' {
+			star = Some((p, t));
+			p += 1;
+		} else if let Some((star_p, star_t)) = star {
+			p = star_p + 1;
+			t = star_t + 1;
+			star = Some((star_p, star_t + 1));
+		} else {
+			return false;
+		}
+	}
+
+	pattern[p..].iter().all(|v| *v == '*')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn date_of_epoch() {
+		assert_eq!(readable_date(0.0), "1970-01-01 00:00 UTC");
+		assert_eq!(readable_date(-60.0), "1969-12-31 23:59 UTC");
+	}
+
+	#[test]
+	fn date_of_leap_day() {
+		assert_eq!(readable_date(1_709_210_040.0), "2024-02-29 12:34 UTC");
+		assert_eq!(readable_date(951_868_800.0), "2000-03-01 00:00 UTC");
+	}
+
+	#[test]
+	fn date_at_end_of_year() {
+		assert_eq!(readable_date(1_704_067_140.0), "2023-12-31 23:59 UTC");
+		assert_eq!(readable_date(1_704_067_199.9), "2023-12-31 23:59 UTC");
+		assert_eq!(readable_date(1_704_067_200.0), "2024-01-01 00:00 UTC");
+	}
+
+	#[test]
+	fn glob_star_matches_anything() {
+		assert!(glob_match("*", ""));
+		assert!(glob_match("*", "journeymap-1.20.jar"));
+		assert!(glob_match("journeymap-*.jar", "journeymap-1.20.jar"));
+		assert!(glob_match("*-*.jar", "a-b-c.jar"));
+	}
+
+	#[test]
+	fn glob_question_mark_matches_one_char() {
+		assert!(glob_match("mod-?.jar", "mod-1.jar"));
+		assert!(!glob_match("mod-?.jar", "mod-12.jar"));
+		assert!(!glob_match("mod-?.jar", "mod-.jar"));
+	}
+
+	#[test]
+	fn glob_trailing_star() {
+		assert!(glob_match("sodium*", "sodium"));
+		assert!(glob_match("sodium*", "sodium-fabric-0.5.jar"));
+		assert!(glob_match("sodium**", "sodium"));
+	}
+
+	#[test]
+	fn glob_no_match() {
+		assert!(!glob_match("sodium*", "iris.jar"));
+		assert!(!glob_match("*.jar", "mod.zip"));
+		assert!(!glob_match("mod.jar", "mod.jar.disabled"));
+		assert!(!glob_match("", "mod.jar"));
+	}
+
+	#[test]
+	fn glob_is_case_sensitive() {
+		// INFO: callers lowercase both sides when they want it case insensitive
+		assert!(!glob_match("Sodium*", "sodium.jar"));
+		assert!(glob_match("Sodium*", "Sodium.jar"));
+	}
+}
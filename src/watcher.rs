@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+use fltk::app;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tokio::time::Duration;
+
+use crate::api::{self, BranchInfo, BranchNames, Fetched, Validators};
+use crate::{AppState, Events, tr};
+
+/// Polling faster than this would only hammer the hoster
+const MIN_INTERVAL_SECS: u64 = 10;
+
+/// Per profile settings of polling the hoster for changes
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct WatchSettings {
+	pub enabled: bool,
+	pub interval_secs: u64,
+	/// Download/delete right away when the current branch changes
+	pub auto_sync: bool,
+}
+
+impl Default for WatchSettings {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			interval_secs: 60,
+			auto_sync: false,
+		}
+	}
+}
+
+static SETTINGS: LazyLock<std::sync::RwLock<WatchSettings>> =
+	LazyLock::new(|| std::sync::RwLock::new(WatchSettings::default()));
+
+pub fn set_settings(settings: WatchSettings) {
+	*SETTINGS.write().unwrap() = settings;
+}
+
+pub fn get_settings() -> WatchSettings {
+	SETTINGS.read().unwrap().clone()
+}
+
+/// What changed on the hoster since the lists were filled
+#[derive(Debug, Clone, Default)]
+pub struct Changes {
+	pub new_branches: Vec<String>,
+	pub removed_branches: Vec<String>,
+	pub added_mods: Vec<String>,
+	pub removed_mods: Vec<String>,
+	pub updated_mods: Vec<String>,
+}
+
+impl Changes {
+	pub fn is_empty(&self) -> bool {
+		self.new_branches.is_empty() && self.removed_branches.is_empty() && !self.mods_changed()
+	}
+
+	pub fn mods_changed(&self) -> bool {
+		!(self.added_mods.is_empty()
+			&& self.removed_mods.is_empty()
+			&& self.updated_mods.is_empty())
+	}
+}
+
+impl fmt::Display for Changes {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut parts = Vec::new();
+
+		if !self.new_branches.is_empty() {
+			parts.push(tr!(
+				"watch.new_branches",
+				names = self.new_branches.join(", ")
+			));
+		}
+		if !self.removed_branches.is_empty() {
+			parts.push(tr!(
+				"watch.removed_branches",
+				names = self.removed_branches.join(", ")
+			));
+		}
+		if !self.added_mods.is_empty() {
+			parts.push(tr!("watch.added_mods", count = self.added_mods.len()));
+		}
+		if !self.removed_mods.is_empty() {
+			parts.push(tr!("watch.removed_mods", count = self.removed_mods.len()));
+		}
+		if !self.updated_mods.is_empty() {
+			parts.push(tr!("watch.updated_mods", count = self.updated_mods.len()));
+		}
+
+		write!(f, "{}", tr!("watch.changed", changes = parts.join(", ")))
+	}
+}
+
+fn diff_branches(changes: &mut Changes, old: &BranchNames, new: &BranchNames) {
+	changes.new_branches = new.iter().filter(|v| !old.contains(v)).cloned().collect();
+	changes.removed_branches = old.iter().filter(|v| !new.contains(v)).cloned().collect();
+}
+
+fn diff_mods(changes: &mut Changes, old: &BranchInfo, new: &BranchInfo) {
+	let old_mods: HashMap<&String, &api::Mod> = old.mods.iter().map(|v| (&v.name, v)).collect();
+	let new_names: HashSet<&String> = new.mods.iter().map(|v| &v.name).collect();
+
+	for mcmod in new.mods.iter() {
+		match old_mods.get(&mcmod.name) {
+			None => changes.added_mods.push(mcmod.name.clone()),
+			Some(old_mod) if old_mod.size != mcmod.size || old_mod.mod_date != mcmod.mod_date => {
+				changes.updated_mods.push(mcmod.name.clone())
+			}
+			_ => {}
+		}
+	}
+
+	changes.removed_mods = old
+		.mods
+		.iter()
+		.filter(|v| !new_names.contains(&v.name))
+		.map(|v| v.name.clone())
+		.collect();
+}
+
+/// Validators of the last responses, only valid for the same address and branch
+#[derive(Default)]
+struct WatchState {
+	api_address: String,
+	branch_name: String,
+	names_validators: Validators,
+	info_validators: Validators,
+}
+
+/// Poll the hoster forever, sends Events::WatchChanges when something changed
+pub async fn watch(fltk_tx: app::Sender<Events>, app_state: Arc<RwLock<AppState>>) {
+	let mut state = WatchState::default();
+
+	loop {
+		let settings = get_settings();
+		tokio::time::sleep(Duration::from_secs(
+			settings.interval_secs.max(MIN_INTERVAL_SECS),
+		))
+		.await;
+
+		if !settings.enabled {
+			continue;
+		}
+
+		// INFO: something (e.g. download) holds the state, check next time
+		if app_state.try_write().is_err() {
+			continue;
+		}
+
+		let app_state_locked = app_state.read().await;
+		let (api_address, branch_name) = match (
+			app_state_locked.server_api_address.clone(),
+			app_state_locked.branch_name.clone(),
+		) {
+			(Some(api_address), Some(branch_name)) => (api_address, branch_name),
+			_ => continue,
+		};
+		let known_branches = app_state_locked.branch_names.clone();
+		let known_info = app_state_locked.branch_info.clone();
+		drop(app_state_locked);
+
+		if state.api_address != api_address {
+			state = WatchState {
+				api_address: api_address.clone(),
+				..Default::default()
+			};
+		}
+		if state.branch_name != branch_name {
+			state.branch_name = branch_name.clone();
+			state.info_validators = Validators::default();
+		}
+
+		let mut changes = Changes::default();
+
+		let branch_names =
+			match api::get_branch_names_if_changed(&api_address, &state.names_validators).await {
+				Ok(Fetched::Changed(branch_names, validators)) => {
+					state.names_validators = validators;
+					diff_branches(&mut changes, &known_branches, &branch_names);
+					Some(branch_names)
+				}
+				Ok(Fetched::NotModified) => None,
+				Err(err) => {
+					println!("watch: cannot get branch names. {}", err);
+					continue;
+				}
+			};
+
+		let branch_info = match api::get_mods_in_branch_if_changed(
+			&api_address,
+			&branch_name,
+			&state.info_validators,
+		)
+		.await
+		{
+			Ok(Fetched::Changed(branch_info, validators)) => {
+				state.info_validators = validators;
+				if let Some(known_info) = known_info.as_ref() {
+					diff_mods(&mut changes, known_info, &branch_info);
+				}
+				Some(branch_info)
+			}
+			Ok(Fetched::NotModified) => None,
+			Err(err) => {
+				println!("watch: cannot get mods. {}", err);
+				None
+			}
+		};
+
+		if changes.is_empty() {
+			continue;
+		}
+
+		println!("{}", changes);
+
+		fltk_tx.send(Events::WatchChanges {
+			branch_names: branch_names.filter(|_| {
+				!changes.new_branches.is_empty() || !changes.removed_branches.is_empty()
+			}),
+			branch_info: branch_info.filter(|_| changes.mods_changed()),
+			changes,
+		});
+	}
+}
@@ -0,0 +1,588 @@
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use fltk::{app, browser, enums, menu, prelude::*};
+
+use crate::api::GroupKind;
+use crate::i18n::Relabel;
+use crate::theme::{self, EntryColor};
+use crate::{tr, utils};
+
+/// What a line of a ModBrowser stands for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+	/// Download list, must be downloaded, can't be unchecked
+	Required,
+	/// Download list, user chooses
+	Optional,
+	/// Delete list, not in the branch anymore
+	Deletable,
+	/// Delete list, optional mod of the branch that is installed
+	OptionalInstalled,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortBy {
+	#[default]
+	Name,
+	/// Biggest first
+	Size,
+	/// Newest first
+	Date,
+}
+
+/// Which entries are shown, to-be-deleted ones always are
+#[derive(Clone, Debug)]
+pub struct Filter {
+	/// Substring, or glob if it has * or ?
+	pub text: String,
+	pub required: bool,
+	pub optional: bool,
+	pub kept: bool,
+}
+
+impl Default for Filter {
+	fn default() -> Self {
+		Self {
+			text: String::new(),
+			required: true,
+			optional: true,
+			kept: true,
+		}
+	}
+}
+
+impl Filter {
+	fn matches(&self, entry: &Entry) -> bool {
+		let shown = match entry.color() {
+			EntryColor::Required => self.required,
+			EntryColor::Optional => self.optional,
+			EntryColor::Kept => self.kept,
+			EntryColor::ToDelete => true,
+		};
+		if !shown {
+			return false;
+		}
+
+		let text = self.text.trim().to_lowercase();
+		let name = entry.name.to_lowercase();
+
+		if text.is_empty() {
+			true
+		} else if text.contains(['*', '?']) {
+			utils::glob_match(&text, &name)
+		} else {
+			name.contains(&text)
+		}
+	}
+}
+
+struct Entry {
+	name: String,
+	checked: bool,
+	kind: EntryKind,
+	size: u64,
+	/// Unix seconds
+	date: f64,
+	/// Index into ModBrowser's groups
+	group: usize,
+	/// Appeared on the server since the user last looked
+	is_new: bool,
+}
+
+impl Entry {
+	fn color(&self) -> EntryColor {
+		match (self.kind, self.checked) {
+			(EntryKind::Required, _) => EntryColor::Required,
+			(EntryKind::Optional, _) => EntryColor::Optional,
+			(EntryKind::Deletable, true) => EntryColor::ToDelete,
+			(EntryKind::Deletable, false) => EntryColor::Kept,
+			(EntryKind::OptionalInstalled, true) => EntryColor::ToDelete,
+			(EntryKind::OptionalInstalled, false) => EntryColor::Optional,
+		}
+	}
+
+	fn is_locked(&self) -> bool {
+		self.kind == EntryKind::Required
+	}
+
+	/// "@." stops format parsing so mod names can't inject codes
+	fn text(&self) -> String {
+		let mut color = theme::current().entry_color(self.color());
+		// INFO: greyed out like a deactivated widget
+		if self.is_locked() {
+			color = color.inactive();
+		}
+
+		format!(
+			"@C{}{}@.{} {}{}",
+			color.bits(),
+			if self.kind == EntryKind::Optional {
+				"@i"
+			} else {
+				""
+			},
+			if self.checked { "[x]" } else { "[  ]" },
+			self.name,
+			if self.is_new {
+				format!(" {}", tr!("main.new_mark"))
+			} else {
+				String::new()
+			}
+		)
+	}
+}
+
+/// Line of the browser
+enum Row {
+	/// Index into entries
+	Entry(usize),
+	/// Horizontal rule between groups
+	Separator,
+	/// Bold title of a group
+	Header(String),
+}
+
+impl Row {
+	fn text(&self, entries: &[Entry]) -> String {
+		match self {
+			Row::Entry(i) => entries[*i].text(),
+			Row::Separator => String::from("@-"),
+			Row::Header(title) => format!("@b@.{}", title),
+		}
+	}
+}
+
+struct Group {
+	/// None for the first, untitled group
+	title: Option<String>,
+	kind: GroupKind,
+}
+
+#[derive(Default)]
+struct Model {
+	groups: Vec<Group>,
+	entries: Vec<Entry>,
+	/// What the browser shows right now
+	rows: Vec<Row>,
+	filter: Filter,
+	sort_by: SortBy,
+}
+
+impl Model {
+	fn entry_at(&self, line: i32) -> Option<&Entry> {
+		let row = usize::try_from(line - 1).ok()?;
+
+		match self.rows.get(row)? {
+			Row::Entry(i) => self.entries.get(*i),
+			_ => None,
+		}
+	}
+
+	fn compare(&self, a: &Entry, b: &Entry) -> Ordering {
+		let by_name = a.name.to_lowercase().cmp(&b.name.to_lowercase());
+
+		match self.sort_by {
+			SortBy::Name => by_name,
+			SortBy::Size => b.size.cmp(&a.size).then(by_name),
+			SortBy::Date => b.date.total_cmp(&a.date).then(by_name),
+		}
+	}
+
+	/// Groups keep their order, entries are filtered and sorted inside them
+	fn build_rows(&mut self) {
+		let mut rows = Vec::new();
+
+		for (group, Group { title, .. }) in self.groups.iter().enumerate() {
+			let mut visible: Vec<usize> = (0..self.entries.len())
+				.filter(|&i| self.entries[i].group == group)
+				.filter(|&i| self.filter.matches(&self.entries[i]))
+				.collect();
+
+			if visible.is_empty() {
+				continue;
+			}
+
+			visible.sort_by(|&a, &b| self.compare(&self.entries[a], &self.entries[b]));
+
+			if let Some(title) = title {
+				if !rows.is_empty() {
+					rows.push(Row::Separator);
+				}
+				rows.push(Row::Header(title.clone()));
+			}
+			rows.extend(visible.into_iter().map(Row::Entry));
+		}
+
+		self.rows = rows;
+	}
+
+	fn checks(&self) -> Vec<bool> {
+		self.entries.iter().map(|v| v.checked).collect()
+	}
+
+	/// Entries whose check differs from before, with their new state
+	fn changes(&self, before: &[bool]) -> Vec<(String, bool)> {
+		self.entries
+			.iter()
+			.zip(before)
+			.filter(|(entry, checked)| entry.checked != **checked)
+			.map(|(entry, _)| (entry.name.clone(), entry.checked))
+			.collect()
+	}
+
+	/// Make checks of group follow its kind, changed is the entry the user just (un)checked
+	fn enforce(&mut self, group: usize, changed: Option<usize>) {
+		let members: Vec<usize> = (0..self.entries.len())
+			.filter(|&i| self.entries[i].group == group && !self.entries[i].is_locked())
+			.collect();
+
+		match self.groups[group].kind {
+			GroupKind::Any => {}
+			GroupKind::OneOf => {
+				// INFO: the one just checked wins, otherwise the first checked one
+				let keep = changed
+					.filter(|&i| self.entries[i].checked)
+					.or_else(|| members.iter().copied().find(|&i| self.entries[i].checked));
+				for i in members {
+					self.entries[i].checked = Some(i) == keep;
+				}
+			}
+			GroupKind::AllOrNothing => {
+				let checked = match changed {
+					Some(i) => self.entries[i].checked,
+					None => members.iter().any(|&i| self.entries[i].checked),
+				};
+				for i in members {
+					self.entries[i].checked = checked;
+				}
+			}
+		}
+	}
+
+	fn enforce_all(&mut self) {
+		for group in 0..self.groups.len() {
+			self.enforce(group, None);
+		}
+	}
+
+	/// Set text of every shown line again
+	fn redraw(&self, browser: &mut browser::HoldBrowser) {
+		for (i, row) in self.rows.iter().enumerate() {
+			browser.set_text(i as i32 + 1, &row.text(&self.entries));
+		}
+		browser.redraw();
+	}
+}
+
+/// CheckBrowser look-alike which colours its lines by the theme and locks required mods
+#[derive(Clone)]
+pub struct ModBrowser {
+	browser: browser::HoldBrowser,
+	model: Rc<RefCell<Model>>,
+	/// Line of the last click, 1 based like fltk
+	clicked: Rc<Cell<i32>>,
+	/// Entries (un)checked by the last click, group rules can change more than the clicked one
+	changed: Rc<RefCell<Vec<(String, bool)>>>,
+}
+
+fltk::widget_extends!(ModBrowser, browser::HoldBrowser, browser);
+
+impl ModBrowser {
+	pub fn new(label: &str) -> Self {
+		let mut browser = browser::HoldBrowser::default()
+			.with_label(label)
+			.with_align(enums::Align::Top);
+		// INFO: selection is removed after every click, so each click is a change
+		browser.set_trigger(enums::CallbackTrigger::Release);
+		browser.clear_visible_focus();
+
+		Self {
+			browser,
+			model: Rc::new(RefCell::new(Model {
+				groups: vec![Group {
+					title: None,
+					kind: GroupKind::Any,
+				}],
+				..Default::default()
+			})),
+			clicked: Rc::new(Cell::new(0)),
+			changed: Rc::new(RefCell::new(Vec::new())),
+		}
+	}
+
+	/// Shown after the next rebuild
+	pub fn add(&mut self, name: &str, checked: bool, kind: EntryKind, size: u64, date: f64) {
+		let mut model = self.model.borrow_mut();
+		let group = model.groups.len() - 1;
+
+		model.entries.push(Entry {
+			name: name.to_string(),
+			checked: checked || kind == EntryKind::Required,
+			kind,
+			size,
+			date,
+			group,
+			is_new: false,
+		});
+	}
+
+	/// Flag entry as new on the server, shown after the next rebuild
+	pub fn mark_new(&mut self, name: &str) {
+		if let Some(entry) = self
+			.model
+			.borrow_mut()
+			.entries
+			.iter_mut()
+			.find(|v| v.name == name)
+		{
+			entry.is_new = true;
+		}
+	}
+
+	/// Following entries go into a new titled group, can't be clicked
+	pub fn add_header(&mut self, title: &str) {
+		self.add_group(title, GroupKind::Any);
+	}
+
+	/// Like add_header, but its entries are checked by kind's rules
+	pub fn add_group(&mut self, title: &str, kind: GroupKind) {
+		self.model.borrow_mut().groups.push(Group {
+			title: Some(title.to_string()),
+			kind,
+		});
+	}
+
+	pub fn clear(&mut self) {
+		{
+			let mut model = self.model.borrow_mut();
+			model.groups = vec![Group {
+				title: None,
+				kind: GroupKind::Any,
+			}];
+			model.entries.clear();
+		}
+		self.rebuild();
+	}
+
+	/// Fill browser from the entries with the current filter and sort
+	pub fn rebuild(&mut self) {
+		let mut model = self.model.borrow_mut();
+		model.build_rows();
+
+		self.browser.clear();
+		for row in model.rows.iter() {
+			self.browser.add(&row.text(&model.entries));
+		}
+		self.clicked.set(0);
+		self.browser.redraw();
+	}
+
+	pub fn set_filter(&mut self, filter: Filter) {
+		self.model.borrow_mut().filter = filter;
+		self.rebuild();
+	}
+
+	pub fn set_sort(&mut self, sort_by: SortBy) {
+		self.model.borrow_mut().sort_by = sort_by;
+		self.rebuild();
+	}
+
+	/// Line last clicked by the user, 0 if none
+	pub fn value(&self) -> i32 {
+		self.clicked.get()
+	}
+
+	/// Mod name of line, None for headers
+	pub fn text(&self, line: i32) -> Option<String> {
+		self.model.borrow().entry_at(line).map(|v| v.name.clone())
+	}
+
+	pub fn checked(&self, line: i32) -> bool {
+		self.model
+			.borrow()
+			.entry_at(line)
+			.is_some_and(|v| v.checked)
+	}
+
+	/// Entries (un)checked by the last click with their new state
+	pub fn changed(&self) -> Vec<(String, bool)> {
+		self.changed.borrow().clone()
+	}
+
+	/// Fix checks breaking group rules (e.g. remembered from before the groups changed)
+	/// Returns the changed entries with their new state
+	pub fn enforce_rules(&mut self) -> Vec<(String, bool)> {
+		let changes = {
+			let mut model = self.model.borrow_mut();
+			let before = model.checks();
+			model.enforce_all();
+			model.changes(&before)
+		};
+
+		self.rebuild();
+		changes
+	}
+
+	/// Check or uncheck every shown entry of kind, returns the changed entries with their new state
+	pub fn set_all_checked(&mut self, kind: EntryKind, checked: bool) -> Vec<(String, bool)> {
+		let changes;
+
+		{
+			let mut model = self.model.borrow_mut();
+			let before = model.checks();
+			let shown: Vec<usize> = model
+				.rows
+				.iter()
+				.filter_map(|v| match v {
+					Row::Entry(i) => Some(*i),
+					_ => None,
+				})
+				.collect();
+
+			for i in shown {
+				let entry = &mut model.entries[i];
+				if entry.kind == kind && !entry.is_locked() {
+					entry.checked = checked;
+				}
+			}
+
+			// INFO: e.g. checking everything keeps only the first of a one-of group
+			model.enforce_all();
+			changes = model.changes(&before);
+		}
+
+		self.rebuild();
+		changes
+	}
+
+	/// Check or uncheck entries by name, returns the changed entries with their new state
+	pub fn set_checked_names(&mut self, names: &[String], checked: bool) -> Vec<(String, bool)> {
+		let changes = {
+			let mut model = self.model.borrow_mut();
+			let before = model.checks();
+
+			for entry in model.entries.iter_mut() {
+				if names.contains(&entry.name) && !entry.is_locked() {
+					entry.checked = checked;
+				}
+			}
+
+			model.enforce_all();
+			model.changes(&before)
+		};
+
+		self.rebuild();
+		changes
+	}
+
+	/// Check entries of kind in names, uncheck the rest, returns the changed entries with their new state
+	pub fn select_only(&mut self, kind: EntryKind, names: &[String]) -> Vec<(String, bool)> {
+		let changes = {
+			let mut model = self.model.borrow_mut();
+			let before = model.checks();
+
+			for entry in model.entries.iter_mut() {
+				if entry.kind == kind && !entry.is_locked() {
+					entry.checked = names.contains(&entry.name);
+				}
+			}
+
+			model.enforce_all();
+			model.changes(&before)
+		};
+
+		self.rebuild();
+		changes
+	}
+
+	/// Redraw lines with the current theme's colours
+	pub fn refresh(&mut self) {
+		self.model.borrow().redraw(&mut self.browser);
+	}
+
+	/// Toggle clicked line (unless locked) then send msg, headers are ignored
+	pub fn emit<T: 'static + Clone + Send + Sync>(&mut self, sender: app::Sender<T>, msg: T) {
+		let model = self.model.clone();
+		let clicked = self.clicked.clone();
+		let changed = self.changed.clone();
+
+		self.browser.set_callback(move |b| {
+			let line = b.value();
+			if line <= 0 {
+				return;
+			}
+			b.deselect(line);
+
+			let mut model = model.borrow_mut();
+			let index = match model.rows.get(line as usize - 1) {
+				Some(Row::Entry(i)) => *i,
+				_ => return,
+			};
+
+			let before = model.checks();
+			let entry = &mut model.entries[index];
+			if !entry.is_locked() {
+				entry.checked = !entry.checked;
+
+				let group = entry.group;
+				model.enforce(group, Some(index));
+			}
+
+			*changed.borrow_mut() = model.changes(&before);
+			model.redraw(b);
+
+			clicked.set(line);
+			sender.send(msg.clone());
+		});
+	}
+}
+
+/// Switch labels, tooltips and choice entries of widget and its children to the new language
+/// Labels with values filled in are left alone, they are set again when the values change
+pub fn relabel(widget: &impl WidgetExt, relabel: &Relabel) {
+	let mut widget = widget.as_base_widget();
+
+	if let Some(text) = relabel.text(&widget.label(), &[]) {
+		widget.set_label(&text);
+	}
+	if let Some(text) = widget.tooltip().and_then(|v| relabel.text(&v, &[])) {
+		widget.set_tooltip(&text);
+	}
+
+	// INFO: only items of "a|b" messages, choices also list branches, themes...
+	if let Some(choice) = menu::Choice::from_dyn_widget(&widget) {
+		for i in 0..choice.size() {
+			if let Some(mut item) = choice.at(i)
+				&& let Some(text) = item.label().and_then(|v| relabel.item(&v))
+			{
+				item.set_label(&text);
+			}
+		}
+	}
+
+	if let Some(group) = widget.as_group() {
+		for i in 0..group.children() {
+			if let Some(child) = group.child(i) {
+				self::relabel(&child, relabel);
+			}
+		}
+	}
+
+	widget.redraw();
+}
+
+/// Switch menu items to the new language
+/// Only menu texts are matched, profile names can look like any other text
+pub fn relabel_menu(menu: &impl MenuExt, relabel: &Relabel) {
+	for i in 0..menu.size() {
+		if let Some(mut item) = menu.at(i)
+			&& let Some(text) = item
+				.label()
+				.and_then(|v| relabel.text(&v, &["menu.", "install_mode."]))
+		{
+			item.set_label(&text);
+		}
+	}
+}